@@ -0,0 +1,165 @@
+//! The daily challenge: one Intermediate board per UTC day, the same for every
+//! player, with a single scored attempt.
+//!
+//! The board is the seed string `xs64:SEED:16x16:40:X,Y` where `SEED` is the
+//! 64-bit FNV-1a hash of `minesweeper-daily:YYYY-MM-DD` (1 if that is 0) and the
+//! first click comes from a SplitMix64 generator seeded with `SEED`:
+//! `X = below(16)`, then `Y = below(16)` (see `engine::rng`).
+//!
+//! Attempts are appended to a plain-text log after a `minesweeper-daily 1`
+//! header, one line per result, and the last line for a day wins:
+//!
+//! ```text
+//! <YYYY-MM-DD> <won 0|1|-> <time_ms> <3bv> <solved_3bv> <clicks> <row>/<row>/...
+//! ```
+//!
+//! Rows hold `o` for a revealed cell, `F` for a flag and `.` for anything else.
+//! A line with `-` for `won` is written when the attempt starts, so quitting early
+//! still uses up the day; unless a result follows it counts as given up.
+
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::date;
+use crate::engine::rng::{self, Algorithm, SeedSpec, SplitMix64};
+use crate::engine::{Board, Error, Topology};
+use crate::paths;
+
+const HEADER: &str = "minesweeper-daily 1";
+const WIDTH: usize = 16;
+const HEIGHT: usize = 16;
+const MINES: usize = 40;
+
+/// One day's challenge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Daily {
+    /// `YYYY-MM-DD` (UTC).
+    pub date: String,
+    /// The board, including its first click.
+    pub spec: SeedSpec,
+}
+
+impl Daily {
+    pub fn today() -> Self { Self::on(date::now_unix()) }
+
+    /// The challenge of the UTC day containing `unix_secs`.
+    pub fn on(unix_secs: u64) -> Self {
+        let date = date::format_ymd(unix_secs);
+        let seed = fnv1a(format!("minesweeper-daily:{}", date).as_bytes()).max(1);
+        let mut rng = SplitMix64::new(seed);
+        let first = (rng::below(&mut rng, WIDTH), rng::below(&mut rng, HEIGHT));
+        let spec = SeedSpec {
+            algorithm: Algorithm::XorShift64, seed, width: WIDTH, height: HEIGHT, mines: MINES,
+            no_guess: false, topology: Topology::Square, wrap: false, first_click: Some(first),
+        };
+        Self { date, spec }
+    }
+
+    /// The board, already opened at the first click.
+    pub fn board(&self) -> Result<Board, Error> { self.spec.board() }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// The result of a day's attempt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    pub date: String,
+    pub won: bool,
+    /// False for the placeholder written when the attempt starts.
+    pub finished: bool,
+    pub time: Duration,
+    pub bbbv: usize,
+    pub solved_bbbv: usize,
+    pub clicks: usize,
+    /// Final board rows in the log's `o`/`F`/`.` notation.
+    pub grid: Vec<String>,
+}
+
+impl Attempt {
+    /// The attempt as the board stands; a game still in progress counts as given up.
+    pub fn from_board(date: &str, board: &Board) -> Self {
+        let stats = board.stats();
+        let grid = (0..board.height()).map(|y| (0..board.width()).map(|x| match board.cell(x, y) {
+            Some(c) if c.revealed() => 'o',
+            Some(c) if c.flagged() => 'F',
+            _ => '.',
+        }).collect()).collect();
+        Self {
+            date: date.to_string(), won: board.won(), finished: !board.alive() || board.won(), time: stats.elapsed,
+            bbbv: stats.bbbv, solved_bbbv: stats.solved_bbbv, clicks: stats.clicks.total(), grid,
+        }
+    }
+
+    /// A spoiler-light summary to paste into chat: the result (✅ won, 💥 lost,
+    /// 🏳 gave up), the metrics and an emoji grid (🟩 revealed, 🚩 flagged,
+    /// ⬛ covered).
+    pub fn share(&self) -> String {
+        let result = if self.won { format!("✅ {:.1}s", self.time.as_secs_f64()) }
+            else if self.finished { format!("💥 {}/{} 3BV", self.solved_bbbv, self.bbbv) }
+            else { format!("🏳 gave up at {}/{} 3BV", self.solved_bbbv, self.bbbv) };
+        let mut s = format!("Minesweeper Daily {} {}\n", self.date, result);
+        let _ = write!(s, "{}x{}/{}  3BV {}  Clicks {}", WIDTH, HEIGHT, MINES, self.bbbv, self.clicks);
+        if self.won && !self.time.is_zero() { let _ = write!(s, "  3BV/s {:.2}", self.bbbv as f64 / self.time.as_secs_f64()); }
+        s.push('\n');
+        for row in &self.grid {
+            s.extend(row.chars().map(|c| match c { 'o' => '🟩', 'F' => '🚩', _ => '⬛' }));
+            s.push('\n');
+        }
+        s
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let f: Vec<&str> = line.split_whitespace().collect();
+        let [date, won, ms, bbbv, solved, clicks, rows] = f[..] else { return None };
+        let (won, finished) = match won { "0" => (false, true), "1" => (true, true), "-" => (false, false), _ => return None };
+        Some(Self {
+            date: date.to_string(), won, finished, time: Duration::from_millis(ms.parse().ok()?),
+            bbbv: bbbv.parse().ok()?, solved_bbbv: solved.parse().ok()?, clicks: clicks.parse().ok()?,
+            grid: rows.split('/').map(str::to_string).collect(),
+        })
+    }
+
+    fn line(&self) -> String {
+        let won = if !self.finished { "-" } else if self.won { "1" } else { "0" };
+        format!("{} {} {} {} {} {} {}", self.date, won, self.time.as_millis(), self.bbbv, self.solved_bbbv, self.clicks, self.grid.join("/"))
+    }
+}
+
+pub struct DailyLog {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl DailyLog {
+    pub fn default_path() -> PathBuf { paths::data_dir().join("daily.txt") }
+
+    /// Load the log; a missing or unreadable file yields an empty log.
+    pub fn load(path: &Path) -> Self {
+        let attempts = fs::read_to_string(path)
+            .map(|text| text.lines().filter(|l| *l != HEADER).filter_map(Attempt::parse).collect())
+            .unwrap_or_default();
+        Self { path: path.to_path_buf(), attempts }
+    }
+
+    /// The latest result recorded for a day.
+    pub fn get(&self, date: &str) -> Option<&Attempt> {
+        self.attempts.iter().rev().find(|a| a.date == date)
+    }
+
+    /// Append a result, writing the header for a new file.
+    pub fn record(&mut self, attempt: Attempt) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() { fs::create_dir_all(dir)?; }
+        let fresh = fs::metadata(&self.path).map_or(true, |m| m.len() == 0);
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        if fresh { writeln!(file, "{}", HEADER)?; }
+        writeln!(file, "{}", attempt.line())?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+//! Minimal calendar helpers (UTC) so timestamps can be shown without extra dependencies.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds since the Unix epoch.
+pub fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Civil (year, month, day) for a Unix timestamp, using Howard Hinnant's algorithm.
+pub fn ymd(unix_secs: u64) -> (i64, u32, u32) {
+    let z = (unix_secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+/// `YYYY-MM-DD` for a Unix timestamp.
+pub fn format_ymd(unix_secs: u64) -> String {
+    let (y, m, d) = ymd(unix_secs);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
@@ -1,4 +1,21 @@
 use std::fmt::{self, Write as _};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub use error::Error;
+use mask::Mask;
+use rng::Algorithm;
+use stats::{Clicks, Stats};
+
+mod error;
+pub mod mask;
+pub mod flags;
+pub mod race;
+pub mod replay;
+pub mod rng;
+pub mod save;
+pub mod solver;
+pub mod stats;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RevealResult {
@@ -7,20 +24,107 @@ pub enum RevealResult {
     HitMine,
 }
 
-#[derive(Clone, Debug)]
+/// How cells are connected to their neighbors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Topology {
+    /// Classic grid: up to 8 neighbors.
+    #[default]
+    Square,
+    /// Hexagonal grid in "odd-r" offset layout: odd rows are shifted half a cell to
+    /// the right, and each cell has up to 6 neighbors.
+    Hex,
+}
+
+impl Topology {
+    pub fn name(self) -> &'static str {
+        match self { Topology::Square => "square", Topology::Hex => "hex" }
+    }
+
+    /// Coordinates of the cells adjacent to `(x, y)` on a `w` x `h` board. With
+    /// `wrap` the edges join up (a torus), so interior and edge cells alike have the
+    /// full set of neighbors once the board is at least 3x3. Hex boards need an even
+    /// height for the wrapped rows to line up.
+    pub fn neighbors(self, w: usize, h: usize, wrap: bool, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let offsets: &[(isize, isize)] = match self {
+            Topology::Square => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+            Topology::Hex if y.is_multiple_of(2) => &[(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)],
+            Topology::Hex => &[(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)],
+        };
+        let (x, y, w, h) = (x as isize, y as isize, w as isize, h as isize);
+        let mut out: Vec<(usize, usize)> = Vec::with_capacity(offsets.len());
+        for &(dx, dy) in offsets {
+            let (mut nx, mut ny) = (x + dx, y + dy);
+            if wrap { (nx, ny) = (nx.rem_euclid(w), ny.rem_euclid(h)); }
+            if nx < 0 || ny < 0 || nx >= w || ny >= h || (nx, ny) == (x, y) { continue; }
+            // On boards narrower than 3 cells the wrapped offsets can repeat.
+            let n = (nx as usize, ny as usize);
+            if !out.contains(&n) { out.push(n); }
+        }
+        out.into_iter()
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name()) }
+}
+
+impl std::str::FromStr for Topology {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "square" => Ok(Topology::Square),
+            "hex" => Ok(Topology::Hex),
+            _ => Err(format!("unknown topology '{}'", s)),
+        }
+    }
+}
+
+/// A player move that can be recorded in the undo history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Reveal(usize, usize),
+    ToggleFlag(usize, usize),
+    Chord(usize, usize),
+}
+
+/// Result of an `undo`/`redo`: the action that was rolled back or re-applied
+/// and the coordinates of every cell whose state changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryStep {
+    pub action: Action,
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Monotonic time source for the game timer, so tests can drive time by hand.
+pub trait Clock: Send + Sync {
+    /// Time elapsed since an arbitrary fixed origin.
+    fn now(&self) -> Duration;
+}
+
+/// Wall-clock `Clock` backed by `Instant`.
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self { Self { origin: Instant::now() } }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration { self.origin.elapsed() }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Cell {
     is_mine: bool,
     adjacent: u8,
     revealed: bool,
     flagged: bool,
+    // A `?` note on a covered cell; unlike a flag it does not block reveals.
+    question: bool,
 }
 
-impl Default for Cell {
-    fn default() -> Self {
-        Self { is_mine: false, adjacent: 0, revealed: false, flagged: false }
-    }
-}
-
+#[derive(Clone)]
 pub struct Board {
     width: usize,
     height: usize,
@@ -31,13 +135,55 @@ pub struct Board {
     won: bool,
     initialized: bool,
     seed: u64,
+    // Generator that turns the seed into a mine layout.
+    rng: Algorithm,
+    // Cell the mines were placed around, once they are.
+    first_click: Option<(usize, usize)>,
+    no_guess: bool,
+    topology: Topology,
+    wrap: bool,
+    // Shape of a non-rectangular board; cells outside it are holes.
+    mask: Option<Mask>,
+    // Mine-hunting: revealing a mine claims it instead of ending the game.
+    hunt: bool,
+    // Whether `toggle_flag` cycles through a question mark after the flag.
+    question_marks: bool,
+    clicks: Clicks,
+    history: Vec<Move>,
+    future: Vec<Move>,
+    clock: Arc<dyn Clock>,
+    // Timer: time banked from finished stretches plus the clock reading when the
+    // current stretch started (None while stopped).
+    timer_banked: Duration,
+    timer_started: Option<Duration>,
+    // Whether the timer was read from a save file rather than measured here.
+    timer_restored: bool,
+    // Whether a move was ever taken back.
+    undo_used: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Status {
+    remaining_safe: usize,
+    alive: bool,
+    won: bool,
+    initialized: bool,
+}
+
+// One undoable move: the changed cells (index, before, after) plus game status.
+#[derive(Clone, Debug)]
+struct Move {
+    action: Action,
+    changes: Vec<(usize, Cell, Cell)>,
+    before: Status,
+    after: Status,
 }
 
 impl Board {
-    pub fn new(width: usize, height: usize, mines: usize, mut seed: u64) -> Result<Self, String> {
-        if width == 0 || height == 0 { return Err("Board dimensions must be positive".into()); }
-        let total = width * height;
-        if mines >= total { return Err("Mines must be less than cells".into()); }
+    pub fn new(width: usize, height: usize, mines: usize, mut seed: u64) -> Result<Self, Error> {
+        if width == 0 || height == 0 { return Err(Error::InvalidDimensions { width, height }); }
+        let total = width.checked_mul(height).ok_or(Error::InvalidDimensions { width, height })?;
+        if mines >= total { return Err(Error::TooManyMines { mines, cells: total }); }
         let mines = mines.min(total.saturating_sub(1));
 
         if seed == 0 { seed = seed_from_time(); }
@@ -45,55 +191,364 @@ impl Board {
         let cells = vec![Cell::default(); total];
         let remaining_safe = total - mines;
 
-        Ok(Self { width, height, mines, cells, remaining_safe, alive: true, won: false, initialized: false, seed })
+        Ok(Self { width, height, mines, cells, remaining_safe, alive: true, won: false, initialized: false, seed, rng: Algorithm::default(), first_click: None, no_guess: false, topology: Topology::Square, wrap: false, mask: None, hunt: false, question_marks: false, clicks: Clicks::default(), history: Vec::new(), future: Vec::new(),
+            clock: Arc::new(SystemClock::default()), timer_banked: Duration::ZERO, timer_started: None, timer_restored: false, undo_used: false })
+    }
+
+    /// A board shaped by `mask`: holes hold no mines, count for no adjacency and
+    /// cannot be played.
+    pub fn from_mask(mask: Mask, mines: usize, seed: u64) -> Result<Self, Error> {
+        let playable = mask.cells();
+        if mines >= playable { return Err(Error::TooManyMines { mines, cells: playable }); }
+        let mut board = Self::new(mask.width(), mask.height(), mines, seed)?;
+        board.remaining_safe = playable - mines;
+        board.mask = Some(mask);
+        Ok(board)
+    }
+
+    /// In no-guess mode the mine layout is regenerated (deterministically from the
+    /// seed) until the board can be cleared by pure logic from the first click.
+    pub fn with_no_guess(mut self, on: bool) -> Self {
+        self.no_guess = on;
+        self
+    }
+
+    /// Place mines with another generator; only meaningful before the first reveal.
+    pub fn with_rng(mut self, rng: Algorithm) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Change how cells neighbor each other; only meaningful before the first reveal.
+    /// Fails for a wrapped hex board of odd height.
+    pub fn with_topology(mut self, topology: Topology) -> Result<Self, Error> {
+        self.topology = topology;
+        self.check_hex_wrap()
     }
 
+    /// Join opposite edges so the board becomes a torus; only meaningful before the
+    /// first reveal. Fails for a hex board of odd height.
+    pub fn with_wrap(mut self, on: bool) -> Result<Self, Error> {
+        self.wrap = on;
+        self.check_hex_wrap()
+    }
+
+    // Wrapped hex rows only line up (and neighbors stay mutual) on an even height.
+    fn check_hex_wrap(self) -> Result<Self, Error> {
+        if self.wrap && self.topology == Topology::Hex && !self.height.is_multiple_of(2) {
+            return Err(Error::HexWrapOddHeight { height: self.height });
+        }
+        Ok(self)
+    }
+
+    /// In mine-hunting mode (the "Flags" variant) revealing a mine uncovers it and
+    /// play goes on; the board is never won or lost on its own.
+    pub fn with_mine_hunt(mut self, on: bool) -> Self {
+        self.hunt = on;
+        self
+    }
+
+    /// With question marks on, `toggle_flag` cycles covered → flag → `?` → covered
+    /// instead of just covered ↔ flag.
+    pub fn with_question_marks(mut self, on: bool) -> Self {
+        self.question_marks = on;
+        self
+    }
+
+    /// Use a custom time source for the game timer.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Step a covered cell's marker to the next state of the flag cycle; `false` if
+    /// the move was rejected.
     pub fn toggle_flag(&mut self, x: usize, y: usize) -> bool {
-        if x >= self.width || y >= self.height { return false; }
-        let i = idx(self.width, x, y);
-        if self.cells[i].revealed { return false; }
-        self.cells[i].flagged = !self.cells[i].flagged;
-        true
+        self.try_toggle_flag(x, y).is_ok()
+    }
+
+    /// Like `toggle_flag`, but says why a rejected move was refused. `Ok` holds
+    /// whether the cell is now flagged.
+    pub fn try_toggle_flag(&mut self, x: usize, y: usize) -> Result<bool, Error> {
+        self.record(Action::ToggleFlag(x, y), |b| b.apply_toggle_flag(x, y))
     }
 
     pub fn reveal(&mut self, x: usize, y: usize) -> RevealResult {
-        if !self.alive || self.won { return RevealResult::NoOp; }
-        if x >= self.width || y >= self.height { return RevealResult::NoOp; }
-        let i = idx(self.width, x, y);
-        if self.cells[i].flagged || self.cells[i].revealed { return RevealResult::NoOp; }
+        self.try_reveal(x, y).unwrap_or(RevealResult::NoOp)
+    }
+
+    /// Like `reveal`, but a rejected move is an `Err` with the reason instead of `NoOp`.
+    pub fn try_reveal(&mut self, x: usize, y: usize) -> Result<RevealResult, Error> {
+        self.record(Action::Reveal(x, y), |b| b.apply_reveal(x, y))
+    }
+
+    /// Reveal all unflagged neighbors of a revealed number whose flagged-neighbor
+    /// count matches its adjacency. A wrongly placed flag makes this hit a mine.
+    pub fn chord(&mut self, x: usize, y: usize) -> RevealResult {
+        self.try_chord(x, y).unwrap_or(RevealResult::NoOp)
+    }
+
+    /// Like `chord`, but a rejected move is an `Err` with the reason instead of `NoOp`.
+    pub fn try_chord(&mut self, x: usize, y: usize) -> Result<RevealResult, Error> {
+        self.record(Action::Chord(x, y), |b| b.apply_chord(x, y))
+    }
+
+    /// Roll back the most recent move, including a move that hit a mine.
+    pub fn undo(&mut self) -> Option<HistoryStep> {
+        let mv = self.history.pop()?;
+        self.undo_used = true;
+        for (i, before, _) in &mv.changes { self.cells[*i] = before.clone(); }
+        self.set_status(mv.before);
+        self.sync_timer();
+        let step = self.step_of(&mv);
+        self.future.push(mv);
+        Some(step)
+    }
+
+    /// Re-apply the most recently undone move.
+    pub fn redo(&mut self) -> Option<HistoryStep> {
+        let mv = self.future.pop()?;
+        for (i, _, after) in &mv.changes { self.cells[*i] = after.clone(); }
+        self.set_status(mv.after);
+        self.sync_timer();
+        let step = self.step_of(&mv);
+        self.history.push(mv);
+        Some(step)
+    }
+
+    pub fn can_undo(&self) -> bool { !self.history.is_empty() }
+    pub fn can_redo(&self) -> bool { !self.future.is_empty() }
+
+    // Run a mutation and, if it changed anything, push it onto the undo history.
+    fn record<R>(&mut self, action: Action, f: impl FnOnce(&mut Self) -> R) -> R {
+        let before_cells = self.cells.clone();
+        let before = self.status();
+        let res = f(self);
+        let after = self.status();
+        let changes: Vec<(usize, Cell, Cell)> = before_cells.into_iter().enumerate()
+            .filter(|(i, c)| *c != self.cells[*i])
+            .map(|(i, c)| (i, c, self.cells[i].clone()))
+            .collect();
+        match action {
+            Action::Reveal(..) => self.clicks.left += 1,
+            Action::ToggleFlag(..) => self.clicks.right += 1,
+            Action::Chord(..) => self.clicks.chord += 1,
+        }
+        if !changes.is_empty() || before != after {
+            self.history.push(Move { action, changes, before, after });
+            self.future.clear();
+        } else {
+            self.clicks.wasted += 1;
+        }
+        self.sync_timer();
+        res
+    }
+
+    // The timer runs from the first reveal until the game is won or lost; undoing
+    // a finished game resumes it and undoing back to an empty board resets it.
+    fn sync_timer(&mut self) {
+        let running = self.initialized && self.alive && !self.won;
+        match (self.timer_started, running) {
+            (None, true) => self.timer_started = Some(self.clock.now()),
+            (Some(start), false) => {
+                self.timer_banked += self.clock.now().saturating_sub(start);
+                self.timer_started = None;
+            }
+            _ => {}
+        }
+        if !self.initialized { self.timer_banked = Duration::ZERO; }
+    }
+
+    fn status(&self) -> Status {
+        Status { remaining_safe: self.remaining_safe, alive: self.alive, won: self.won, initialized: self.initialized }
+    }
+
+    fn set_status(&mut self, st: Status) {
+        self.remaining_safe = st.remaining_safe;
+        self.alive = st.alive;
+        self.won = st.won;
+        self.initialized = st.initialized;
+    }
+
+    fn step_of(&self, mv: &Move) -> HistoryStep {
+        let cells = mv.changes.iter().map(|(i, _, _)| (i % self.width, i / self.width)).collect();
+        HistoryStep { action: mv.action, cells }
+    }
+
+    // The cell index for (x, y), or why it cannot be played at all.
+    fn check_cell(&self, x: usize, y: usize) -> Result<usize, Error> {
+        if x >= self.width || y >= self.height { return Err(Error::OutOfBounds { x, y }); }
+        if !self.playable(x, y) { return Err(Error::Hole { x, y }); }
+        Ok(idx(self.width, x, y))
+    }
+
+    fn apply_toggle_flag(&mut self, x: usize, y: usize) -> Result<bool, Error> {
+        let i = self.check_cell(x, y)?;
+        if self.cells[i].revealed { return Err(Error::AlreadyRevealed { x, y }); }
+        let c = &mut self.cells[i];
+        (c.flagged, c.question) = match (c.flagged, c.question) {
+            (false, false) => (true, false),
+            (true, _) if self.question_marks => (false, true),
+            _ => (false, false),
+        };
+        Ok(c.flagged)
+    }
+
+    fn apply_reveal(&mut self, x: usize, y: usize) -> Result<RevealResult, Error> {
+        if !self.alive || self.won { return Err(Error::GameOver); }
+        let i = self.check_cell(x, y)?;
+        if self.cells[i].revealed { return Err(Error::AlreadyRevealed { x, y }); }
+        if self.cells[i].flagged { return Err(Error::Flagged { x, y }); }
 
         if !self.initialized { self.initialize(x, y); }
-        if self.cells[i].is_mine { self.alive = false; return RevealResult::HitMine; }
+        if self.cells[i].is_mine {
+            self.cells[i].question = false;
+            if self.hunt { self.cells[i].revealed = true; } else { self.alive = false; }
+            return Ok(RevealResult::HitMine);
+        }
 
         // Flood-fill reveal when adjacent == 0
         self.flood_reveal(x, y);
-        if self.remaining_safe == 0 && self.alive {
+        if self.remaining_safe == 0 && self.alive && !self.hunt {
             self.won = true;
         }
-        RevealResult::RevealedSafe
+        Ok(RevealResult::RevealedSafe)
+    }
+
+    fn apply_chord(&mut self, x: usize, y: usize) -> Result<RevealResult, Error> {
+        if !self.alive || self.won { return Err(Error::GameOver); }
+        let i = self.check_cell(x, y)?;
+        if !self.cells[i].revealed || self.cells[i].adjacent == 0 { return Err(Error::NotANumber { x, y }); }
+
+        let around: Vec<(usize, usize)> = self.neighbors(x, y).collect();
+        let flags = around.iter().filter(|&&(nx, ny)| self.cells[idx(self.width, nx, ny)].flagged).count();
+        let needed = self.cells[i].adjacent as usize;
+        if flags != needed { return Err(Error::FlagMismatch { x, y, flags, needed }); }
+
+        let mut revealed_any = false;
+        let mut hit = false;
+        for (nx, ny) in around {
+            let ni = idx(self.width, nx, ny);
+            let c = &self.cells[ni];
+            if c.flagged || c.revealed { continue; }
+            if c.is_mine {
+                hit = true;
+                if self.hunt { self.cells[ni].revealed = true; }
+                continue;
+            }
+            self.flood_reveal(nx, ny);
+            revealed_any = true;
+        }
+        if hit {
+            if !self.hunt { self.alive = false; }
+            return Ok(RevealResult::HitMine);
+        }
+        if !revealed_any { return Err(Error::NothingToChord { x, y }); }
+        if self.remaining_safe == 0 && !self.hunt { self.won = true; }
+        Ok(RevealResult::RevealedSafe)
     }
 
     fn initialize(&mut self, safe_x: usize, safe_y: usize) {
         if self.initialized { return; }
         let total = self.width * self.height;
         let safe_idx = idx(self.width, safe_x, safe_y);
-        let mut positions: Vec<usize> = (0..total).filter(|&p| p != safe_idx).collect();
-        let mut prng = XorShift64::new(self.seed);
-        fisher_yates_shuffle(&mut positions, &mut prng);
-        for &pos in &positions[..self.mines] {
-            self.cells[pos].is_mine = true;
+        let mut prng = self.rng.generator(self.seed);
+        self.first_click = Some((safe_x, safe_y));
+        if !self.no_guess {
+            let mut positions: Vec<usize> = (0..total).filter(|&p| p != safe_idx && self.playable_idx(p)).collect();
+            rng::shuffle(&mut positions, prng.as_mut());
+            self.place_mines(&positions[..self.mines]);
+            self.initialized = true;
+            return;
         }
-        self.compute_adjacency();
+
+        // No-guess: keep the first click's neighborhood clear when there is room so it
+        // opens an area, then draw layouts from the same PRNG stream until one is
+        // solvable. If none is within the attempt budget, keep the one that logic
+        // got furthest on.
+        let opening: Vec<usize> = std::iter::once(safe_idx)
+            .chain(self.neighbors(safe_x, safe_y).map(|(x, y)| idx(self.width, x, y)))
+            .collect();
+        // Before the first reveal `remaining_safe` is the number of safe cells to place.
+        let excluded = if self.remaining_safe >= opening.len() { &opening[..] } else { &opening[..1] };
+        let mut positions: Vec<usize> = (0..total).filter(|&p| !excluded.contains(&p) && self.playable_idx(p)).collect();
+        let mut best: Option<(usize, Vec<usize>)> = None;
+        let mut work = NO_GUESS_WORK;
+        for _ in 0..NO_GUESS_ATTEMPTS {
+            rng::shuffle(&mut positions, prng.as_mut());
+            self.place_mines(&positions[..self.mines]);
+            let left = self.logic_leftover(safe_x, safe_y, &mut work);
+            if best.as_ref().is_none_or(|(b, _)| left < *b) { best = Some((left, positions[..self.mines].to_vec())); }
+            if left == 0 || work == 0 { break; }
+        }
+        if let Some((_, layout)) = best { self.place_mines(&layout); }
         self.initialized = true;
     }
 
+    fn playable_idx(&self, i: usize) -> bool { self.playable(i % self.width, i / self.width) }
+
+    fn place_mines(&mut self, layout: &[usize]) {
+        for c in self.cells.iter_mut() { c.is_mine = false; }
+        for &pos in layout { self.cells[pos].is_mine = true; }
+        self.compute_adjacency();
+    }
+
+    // Number of safe cells pure logic leaves covered when starting at (x, y). Each
+    // solver pass costs one unit of `work` per cell; the pass that runs it out is the last.
+    fn logic_leftover(&self, x: usize, y: usize, work: &mut usize) -> usize {
+        let mut sim = self.clone();
+        sim.initialized = true;
+        sim.history.clear();
+        if !matches!(sim.apply_reveal(x, y), Ok(RevealResult::RevealedSafe)) { return sim.remaining_safe; }
+        while !sim.won && *work > 0 {
+            *work = work.saturating_sub(sim.cells.len());
+            let d = solver::solve(&sim);
+            if d.safe.is_empty() { break; }
+            for (mx, my) in d.mines { let _ = sim.apply_toggle_flag(mx, my); }
+            for (sx, sy) in d.safe { let _ = sim.apply_reveal(sx, sy); }
+        }
+        sim.remaining_safe
+    }
+
+    // 3BV of the layout and the part of it already revealed. Each opening (a
+    // connected region of zeros plus its numbered border) counts once, and is
+    // solved once any of its zeros is revealed; every other safe cell counts once.
+    fn bbbv_counts(&self) -> (usize, usize) {
+        if !self.initialized { return (0, 0); }
+        let mut seen = vec![false; self.cells.len()];
+        let (mut total, mut solved) = (0, 0);
+        for i in 0..self.cells.len() {
+            let c = &self.cells[i];
+            if seen[i] || c.is_mine || c.adjacent != 0 || !self.playable_idx(i) { continue; }
+            total += 1;
+            let mut open = false;
+            let mut stack = vec![i];
+            seen[i] = true;
+            while let Some(j) = stack.pop() {
+                if self.cells[j].adjacent != 0 { continue; }
+                open |= self.cells[j].revealed;
+                for (nx, ny) in self.neighbors(j % self.width, j / self.width) {
+                    let k = idx(self.width, nx, ny);
+                    if !seen[k] { seen[k] = true; stack.push(k); }
+                }
+            }
+            if open { solved += 1; }
+        }
+        for (i, c) in self.cells.iter().enumerate() {
+            if seen[i] || c.is_mine || !self.playable_idx(i) { continue; }
+            total += 1;
+            if c.revealed { solved += 1; }
+        }
+        (total, solved)
+    }
+
     fn compute_adjacency(&mut self) {
         for y in 0..self.height {
             for x in 0..self.width {
                 let i0 = idx(self.width, x, y);
-                if self.cells[i0].is_mine { continue; }
+                if self.cells[i0].is_mine || !self.playable(x, y) { continue; }
                 let mut c = 0u8;
-                for (nx, ny) in neighbors(self.width, self.height, x, y) {
+                for (nx, ny) in self.neighbors(x, y) {
                     if self.cells[idx(self.width, nx, ny)].is_mine { c += 1; }
                 }
                 self.cells[i0].adjacent = c;
@@ -108,9 +563,10 @@ impl Board {
             if self.cells[i].revealed || self.cells[i].flagged { continue; }
             if self.cells[i].is_mine { continue; }
             self.cells[i].revealed = true;
+            self.cells[i].question = false;
             if self.remaining_safe > 0 { self.remaining_safe -= 1; }
             if self.cells[i].adjacent == 0 {
-                for (nx, ny) in neighbors(self.width, self.height, cx, cy) {
+                for (nx, ny) in self.neighbors(cx, cy) {
                     let ni = idx(self.width, nx, ny);
                     if !self.cells[ni].revealed && !self.cells[ni].is_mine {
                         stack.push((nx, ny));
@@ -121,33 +577,41 @@ impl Board {
     }
 
     pub fn render(&self, show_all: bool, one_based: bool) -> String {
+        // Hex boards use 4-column cells so odd rows can be offset by half a cell.
+        let hex = self.topology == Topology::Hex;
+        let step = if hex { 4 } else { 3 };
         let mut s = String::new();
         // Column header
         s.push_str("    ");
         for x in 0..self.width {
             let label = if one_based { x + 1 } else { x };
-            let _ = write!(s, "{:>2} ", label);
+            let _ = write!(s, "{:>2}{}", label, " ".repeat(step - 2));
         }
         s.push('\n');
         s.push_str("   ");
-        s.push_str(&"-".repeat(self.width * 3 + 1));
+        s.push_str(&"-".repeat(self.width * step + 1));
         s.push('\n');
 
         for y in 0..self.height {
             let row_label = if one_based { y + 1 } else { y };
             let _ = write!(s, "{:>2} | ", row_label);
+            if hex && y % 2 == 1 { s.push_str("  "); }
             for x in 0..self.width {
                 let c = &self.cells[idx(self.width, x, y)];
-                let ch = if show_all && c.is_mine {
+                let ch = if !self.playable(x, y) {
+                    ' '
+                } else if show_all && c.is_mine {
                     '*'
                 } else if c.revealed {
                     if c.is_mine { '*' } else if c.adjacent == 0 { ' ' } else { char::from_digit(c.adjacent as u32, 10).unwrap_or('?') }
                 } else if c.flagged {
                     'F'
+                } else if c.question {
+                    '?'
                 } else {
                     '.'
                 };
-                let _ = write!(s, "{}  ", ch);
+                let _ = write!(s, "{}{}", ch, " ".repeat(step - 1));
             }
             s.push('\n');
         }
@@ -155,51 +619,19 @@ impl Board {
     }
 }
 
-fn idx(w: usize, x: usize, y: usize) -> usize { y * w + x }
-
-fn neighbors(w: usize, h: usize, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
-    let x = x as isize; let y = y as isize; let w = w as isize; let h = h as isize;
-    let mut out = Vec::with_capacity(8);
-    for dy in -1..=1 {
-        for dx in -1..=1 {
-            if dx == 0 && dy == 0 { continue; }
-            let nx = x + dx; let ny = y + dy;
-            if nx >= 0 && ny >= 0 && nx < w && ny < h {
-                out.push((nx as usize, ny as usize));
-            }
-        }
-    }
-    out.into_iter()
-}
-
-// Simple xorshift64 PRNG to avoid external dependencies.
-struct XorShift64 { state: u64 }
-impl XorShift64 {
-    fn new(seed: u64) -> Self { Self { state: seed.max(1) } }
-    fn next_u64(&mut self) -> u64 {
-        let mut x = self.state;
-        x ^= x << 13;
-        x ^= x >> 7;
-        x ^= x << 17;
-        self.state = x;
-        x
-    }
-    fn next_usize(&mut self) -> usize { (self.next_u64() >> 1) as usize }
-}
+// Changing either budget (or the solver) can change no-guess layouts: bump
+// `rng::NO_GUESS_VERSION` with it.
+const NO_GUESS_ATTEMPTS: usize = 500;
+// Solver passes times cells that no-guess generation may spend in total, so big
+// boards give up on a solvable layout after a few attempts instead of minutes.
+const NO_GUESS_WORK: usize = 1 << 21;
 
-fn fisher_yates_shuffle<T>(arr: &mut [T], prng: &mut XorShift64) {
-    // Standard FY: for i from n-1 down to 1, swap i with random j in [0, i]
-    let n = arr.len();
-    for i in (1..n).rev() {
-        let j = prng.next_usize() % (i + 1);
-        arr.swap(i, j);
-    }
-}
+fn idx(w: usize, x: usize, y: usize) -> usize { y * w + x }
 
 fn seed_from_time() -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
     let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
-    now.as_nanos() as u64 ^ (now.as_secs() as u64).rotate_left(32)
+    now.as_nanos() as u64 ^ now.as_secs().rotate_left(32)
 }
 
 impl fmt::Display for Board {
@@ -213,9 +645,46 @@ impl Board {
     pub fn width(&self) -> usize { self.width }
     pub fn height(&self) -> usize { self.height }
     pub fn mines(&self) -> usize { self.mines }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn rng(&self) -> Algorithm { self.rng }
+    /// The cell the mines were placed around; `None` before the first reveal.
+    pub fn first_click(&self) -> Option<(usize, usize)> { self.first_click.filter(|_| self.initialized) }
+    pub fn no_guess(&self) -> bool { self.no_guess }
+    pub fn topology(&self) -> Topology { self.topology }
+    pub fn wrap(&self) -> bool { self.wrap }
+    pub fn mine_hunt(&self) -> bool { self.hunt }
+    pub fn question_marks(&self) -> bool { self.question_marks }
     pub fn remaining_safe(&self) -> usize { self.remaining_safe }
     pub fn alive(&self) -> bool { self.alive }
     pub fn won(&self) -> bool { self.won }
+    /// Time played so far; stops when the game is won or lost.
+    pub fn elapsed(&self) -> Duration {
+        self.timer_banked + self.timer_started.map_or(Duration::ZERO, |s| self.clock.now().saturating_sub(s))
+    }
+    /// True if the elapsed time came from a loaded save, so it cannot be trusted.
+    pub fn timer_restored(&self) -> bool { self.timer_restored }
+    /// True once any move has been undone.
+    pub fn undo_used(&self) -> bool { self.undo_used }
+    /// 3BV, clicks and time so far; see `stats::Stats` for the derived rates.
+    pub fn stats(&self) -> Stats {
+        let (bbbv, solved_bbbv) = self.bbbv_counts();
+        Stats { bbbv, solved_bbbv, clicks: self.clicks, elapsed: self.elapsed() }
+    }
+    pub fn flags(&self) -> usize { self.cells.iter().filter(|c| c.flagged).count() }
+    /// Mines minus flags, as shown by the classic mine counter (negative when over-flagged).
+    pub fn mines_left(&self) -> isize { self.mines as isize - self.flags() as isize }
+    /// Coordinates of every cell adjacent to `(x, y)`.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let out: Vec<(usize, usize)> = self.topology.neighbors(self.width, self.height, self.wrap, x, y)
+            .filter(|&(nx, ny)| self.playable(nx, ny))
+            .collect();
+        out.into_iter()
+    }
+    /// True for in-bounds cells that are part of the board (not mask holes).
+    pub fn playable(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.mask.as_ref().is_none_or(|m| m.playable(x, y))
+    }
+    pub fn mask(&self) -> Option<&Mask> { self.mask.as_ref() }
     pub fn cell(&self, x: usize, y: usize) -> Option<&Cell> {
         if x < self.width && y < self.height { Some(&self.cells[idx(self.width, x, y)]) } else { None }
     }
@@ -226,4 +695,5 @@ impl Cell {
     pub fn adjacent(&self) -> u8 { self.adjacent }
     pub fn revealed(&self) -> bool { self.revealed }
     pub fn flagged(&self) -> bool { self.flagged }
+    pub fn questioned(&self) -> bool { self.question }
 }
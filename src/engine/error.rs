@@ -0,0 +1,99 @@
+//! The engine's error type.
+
+use std::fmt;
+use std::io;
+
+use super::mask::MaskError;
+use super::replay::ReplayError;
+use super::rng::SeedError;
+use super::save::SaveError;
+
+/// Everything the engine can refuse: impossible board parameters, moves the rules
+/// do not allow, and files that cannot be loaded. Coordinates are 0-based in the
+/// variants and shown 1-based by `Display`.
+#[derive(Debug)]
+pub enum Error {
+    /// Width or height is zero, or the cell count overflows `usize`.
+    InvalidDimensions { width: usize, height: usize },
+    /// Wrapped hex boards need an even number of rows.
+    HexWrapOddHeight { height: usize },
+    /// At least one cell must stay free of mines.
+    TooManyMines { mines: usize, cells: usize },
+    OutOfBounds { x: usize, y: usize },
+    /// The cell is a hole in the board's mask.
+    Hole { x: usize, y: usize },
+    /// The game is already won or lost.
+    GameOver,
+    AlreadyRevealed { x: usize, y: usize },
+    /// Flagged cells must be unflagged before they can be revealed.
+    Flagged { x: usize, y: usize },
+    /// Chording needs a revealed number.
+    NotANumber { x: usize, y: usize },
+    /// Chording needs exactly as many flags around the number as its value.
+    FlagMismatch { x: usize, y: usize, flags: usize, needed: usize },
+    /// Every neighbor of the chorded number is already revealed or flagged.
+    NothingToChord { x: usize, y: usize },
+    Save(SaveError),
+    Replay(ReplayError),
+    Mask(MaskError),
+    Seed(SeedError),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidDimensions { width, height } => write!(f, "board dimensions must be positive (got {}x{})", width, height),
+            Error::HexWrapOddHeight { height } => write!(f, "wrapped hex boards need an even height so the offset rows line up (got {})", height),
+            Error::TooManyMines { mines, cells } => write!(f, "{} mines do not fit on {} cells; at least one cell must be safe", mines, cells),
+            Error::OutOfBounds { x, y } => write!(f, "({},{}) is outside the board", x + 1, y + 1),
+            Error::Hole { x, y } => write!(f, "({},{}) is not part of the board", x + 1, y + 1),
+            Error::GameOver => write!(f, "the game is over"),
+            Error::AlreadyRevealed { x, y } => write!(f, "({},{}) is already revealed", x + 1, y + 1),
+            Error::Flagged { x, y } => write!(f, "({},{}) is flagged; unflag it first", x + 1, y + 1),
+            Error::NotANumber { x, y } => write!(f, "({},{}) is not a revealed number", x + 1, y + 1),
+            Error::FlagMismatch { x, y, flags, needed } => write!(
+                f, "the {} at ({},{}) has {} flag{} around it", needed, x + 1, y + 1, flags, if *flags == 1 { "" } else { "s" }
+            ),
+            Error::NothingToChord { x, y } => write!(f, "every neighbor of ({},{}) is already open or flagged", x + 1, y + 1),
+            Error::Save(e) => e.fmt(f),
+            Error::Replay(e) => e.fmt(f),
+            Error::Mask(e) => e.fmt(f),
+            Error::Seed(e) => e.fmt(f),
+            Error::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Save(e) => Some(e),
+            Error::Replay(e) => Some(e),
+            Error::Mask(e) => Some(e),
+            Error::Seed(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SaveError> for Error {
+    fn from(e: SaveError) -> Self { Error::Save(e) }
+}
+
+impl From<ReplayError> for Error {
+    fn from(e: ReplayError) -> Self { Error::Replay(e) }
+}
+
+impl From<MaskError> for Error {
+    fn from(e: MaskError) -> Self { Error::Mask(e) }
+}
+
+impl From<SeedError> for Error {
+    fn from(e: SeedError) -> Self { Error::Seed(e) }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self { Error::Io(e) }
+}
@@ -0,0 +1,58 @@
+//! The "Flags" variant: two players take turns revealing cells on one board.
+//! Revealing a mine claims it for the player and grants another turn; revealing
+//! a safe cell passes the turn. The first to claim more than half the mines wins.
+
+use super::{Board, Error, RevealResult};
+
+pub const PLAYERS: usize = 2;
+
+/// A two-player mine-hunting match on a shared board.
+#[derive(Clone)]
+pub struct FlagsGame {
+    board: Board,
+    scores: [usize; PLAYERS],
+    turn: usize,
+    // Mines in the order they were claimed, with the claiming player.
+    claims: Vec<(usize, usize, usize)>,
+}
+
+impl FlagsGame {
+    /// Start a match on a fresh board; player 0 moves first. An odd mine count
+    /// rules out a tie.
+    pub fn new(board: Board) -> Self {
+        Self { board: board.with_mine_hunt(true), scores: [0; PLAYERS], turn: 0, claims: Vec::new() }
+    }
+
+    pub fn board(&self) -> &Board { &self.board }
+    /// Player whose turn it is.
+    pub fn turn(&self) -> usize { self.turn }
+    pub fn scores(&self) -> [usize; PLAYERS] { self.scores }
+    /// Claimed mines as `(x, y, player)`.
+    pub fn claims(&self) -> &[(usize, usize, usize)] { &self.claims }
+    /// Mines a player must claim to win outright.
+    pub fn target(&self) -> usize { self.board.mines() / 2 + 1 }
+
+    /// The player who has claimed more than half the mines.
+    pub fn winner(&self) -> Option<usize> {
+        (0..PLAYERS).find(|&p| self.scores[p] >= self.target())
+    }
+
+    /// True once somebody won or every mine is claimed (a tie on an even count).
+    pub fn finished(&self) -> bool {
+        self.winner().is_some() || self.claims.len() == self.board.mines()
+    }
+
+    /// Reveal a cell for the player to move. `HitMine` means the mine was claimed
+    /// and the same player goes again.
+    pub fn reveal(&mut self, x: usize, y: usize) -> Result<RevealResult, Error> {
+        if self.finished() { return Err(Error::GameOver); }
+        let result = self.board.try_reveal(x, y)?;
+        if result == RevealResult::HitMine {
+            self.scores[self.turn] += 1;
+            self.claims.push((x, y, self.turn));
+        } else {
+            self.turn = (self.turn + 1) % PLAYERS;
+        }
+        Ok(result)
+    }
+}
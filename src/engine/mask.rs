@@ -0,0 +1,109 @@
+//! Board shapes other than rectangles.
+//!
+//! A mask file is ASCII art where `#` is a playable cell and a space is a hole:
+//!
+//! ```text
+//!  ## ##
+//! #######
+//!  #####
+//!   ###
+//!    #
+//! ```
+//!
+//! Blank rows and columns around the shape are trimmed, and short lines are padded
+//! with holes.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Mask {
+    width: usize,
+    height: usize,
+    playable: Vec<bool>,
+}
+
+#[derive(Debug)]
+pub enum MaskError {
+    Io(io::Error),
+    /// No `#` cells at all.
+    Empty,
+    InvalidChar { x: usize, y: usize, ch: char },
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaskError::Io(e) => write!(f, "cannot read mask file: {}", e),
+            MaskError::Empty => write!(f, "mask has no playable '#' cells"),
+            MaskError::InvalidChar { x, y, ch } => write!(
+                f, "invalid mask character '{}' at column {}, row {} (use '#' for cells and spaces for holes)", ch.escape_default(), x + 1, y + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MaskError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self { MaskError::Io(e) => Some(e), _ => None }
+    }
+}
+
+impl From<io::Error> for MaskError {
+    fn from(e: io::Error) -> Self { MaskError::Io(e) }
+}
+
+impl Mask {
+    /// A mask from row-major playable flags; `None` if the length does not match.
+    pub fn new(width: usize, height: usize, playable: Vec<bool>) -> Option<Self> {
+        if playable.len() != width * height { return None; }
+        Some(Self { width, height, playable })
+    }
+
+    /// Parse mask ASCII art.
+    pub fn parse(text: &str) -> Result<Self, MaskError> {
+        let lines: Vec<&str> = text.lines().map(|l| l.trim_end_matches('\r')).collect();
+        for (y, line) in lines.iter().enumerate() {
+            if let Some((x, ch)) = line.chars().enumerate().find(|&(_, c)| c != '#' && c != ' ') {
+                return Err(MaskError::InvalidChar { x, y, ch });
+            }
+        }
+        let rows: Vec<&str> = lines.iter().map(|l| l.trim_end()).collect();
+        let top = rows.iter().position(|r| !r.is_empty()).ok_or(MaskError::Empty)?;
+        let bottom = rows.iter().rposition(|r| !r.is_empty()).ok_or(MaskError::Empty)?;
+        let rows = &rows[top..=bottom];
+        let left = rows.iter().filter(|r| !r.is_empty()).map(|r| r.len() - r.trim_start().len()).min().unwrap_or(0);
+        let width = rows.iter().map(|r| r.len().saturating_sub(left)).max().unwrap_or(0);
+        let mut playable = vec![false; width * rows.len()];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().skip(left).enumerate() { playable[y * width + x] = ch == '#'; }
+        }
+        Ok(Self { width, height: rows.len(), playable })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, MaskError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+
+    /// False for holes and for coordinates outside the mask.
+    pub fn playable(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.playable[y * self.width + x]
+    }
+
+    /// Number of playable cells.
+    pub fn cells(&self) -> usize { self.playable.iter().filter(|&&p| p).count() }
+
+    /// Stable 64-bit FNV-1a hash of the shape, for telling masks apart in score tables.
+    pub fn fingerprint(&self) -> u64 {
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut eat = |b: u8| { h ^= b as u64; h = h.wrapping_mul(0x0100_0000_01b3); };
+        for b in (self.width as u64).to_le_bytes().into_iter().chain((self.height as u64).to_le_bytes()) { eat(b); }
+        for &p in &self.playable { eat(p as u8); }
+        h
+    }
+}
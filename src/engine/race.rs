@@ -0,0 +1,76 @@
+//! Two-player race: both players get an identical board, opened at the same
+//! cell, and the first to clear theirs wins. Hitting a mine loses the race on
+//! the spot.
+
+use std::time::{Duration, Instant};
+
+use super::{Action, Board, Error, RevealResult};
+
+/// How a race ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    /// Index of the winning player, or `None` when the opening already cleared
+    /// both boards.
+    pub winner: Option<usize>,
+    /// True if the loser hit a mine rather than being outpaced.
+    pub by_mine: bool,
+    /// Race time when it was decided.
+    pub time: Duration,
+}
+
+/// Two copies of one board plus the shared race clock.
+pub struct Race {
+    boards: [Board; 2],
+    opening: (usize, usize),
+    start: Instant,
+    outcome: Option<Outcome>,
+}
+
+impl Race {
+    /// Start a race on `board`, which must not have been played yet: the cell
+    /// nearest the center is revealed first, so both players see the same layout
+    /// and the same opening.
+    pub fn new(mut board: Board) -> Self {
+        let opening = opening(&board);
+        let _ = board.reveal(opening.0, opening.1);
+        let start = Instant::now();
+        let outcome = board.won().then_some(Outcome { winner: None, by_mine: false, time: Duration::ZERO });
+        Self { boards: [board.clone(), board], opening, start, outcome }
+    }
+
+    pub fn board(&self, player: usize) -> &Board { &self.boards[player] }
+    pub fn opening(&self) -> (usize, usize) { self.opening }
+    pub fn outcome(&self) -> Option<Outcome> { self.outcome }
+
+    /// Shared race time; stops once the race is decided.
+    pub fn elapsed(&self) -> Duration {
+        self.outcome.map_or_else(|| self.start.elapsed(), |o| o.time)
+    }
+
+    /// Play a move on `player`'s board. There is no undo in a race.
+    pub fn play(&mut self, player: usize, action: Action) -> Result<RevealResult, Error> {
+        if self.outcome.is_some() { return Err(Error::GameOver); }
+        let board = &mut self.boards[player];
+        let result = match action {
+            Action::Reveal(x, y) => board.try_reveal(x, y)?,
+            Action::ToggleFlag(x, y) => { board.try_toggle_flag(x, y)?; RevealResult::NoOp }
+            Action::Chord(x, y) => board.try_chord(x, y)?,
+        };
+        let time = self.start.elapsed();
+        if !board.alive() {
+            self.outcome = Some(Outcome { winner: Some(1 - player), by_mine: true, time });
+        } else if board.won() {
+            self.outcome = Some(Outcome { winner: Some(player), by_mine: false, time });
+        }
+        Ok(result)
+    }
+}
+
+// The playable cell closest to the middle of the board.
+fn opening(board: &Board) -> (usize, usize) {
+    let (cx, cy) = (board.width() / 2, board.height() / 2);
+    (0..board.height()).flat_map(|y| (0..board.width()).map(move |x| (x, y)))
+        .filter(|&(x, y)| board.playable(x, y))
+        .min_by_key(|&(x, y)| x.abs_diff(cx).pow(2) + y.abs_diff(cy).pow(2))
+        .unwrap_or((0, 0))
+}
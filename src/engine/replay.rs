@@ -0,0 +1,283 @@
+//! Replay recording and playback.
+//!
+//! Mine placement is a pure function of the seed and the first revealed cell, so a
+//! replay only needs the board parameters plus the timed list of player actions:
+//!
+//! ```text
+//! minesweeper-replay 1
+//! size 9 9
+//! mines 10
+//! seed 12345
+//! rng xs64
+//! no_guess false
+//! topology square
+//! wrap false
+//! question_marks false
+//! mine_hunt false
+//! events
+//! 0 reveal 4 4
+//! 1830 flag 0 2
+//! 2410 chord 1 2
+//! 3000 undo
+//! ```
+//!
+//! Event lines are `<milliseconds since start> <op> [x y]` with 0-based coordinates.
+//! A missing `topology` line means a square grid, a missing `wrap` line a board with edges,
+//! a missing `question_marks` line flag toggles without `?`, a missing `mine_hunt` line a
+//! board where mines end the game and a missing `rng` line the `xs64` generator.
+//! Shaped boards add one `mask <row>` line per row, with `#` for cells and `-` for holes.
+
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use super::mask::Mask;
+use super::rng::Algorithm;
+use super::{Action, Board, Error, Topology};
+
+pub const MAGIC: &str = "minesweeper-replay";
+pub const VERSION: u32 = 1;
+
+/// One recorded input: a board action or an undo/redo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Move(Action),
+    Undo,
+    Redo,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub at: Duration,
+    pub step: Step,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub seed: u64,
+    pub rng: Algorithm,
+    pub no_guess: bool,
+    pub topology: Topology,
+    pub wrap: bool,
+    pub question_marks: bool,
+    /// Mine-hunting board (the Flags variant).
+    pub mine_hunt: bool,
+    pub mask: Option<Mask>,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    BadHeader,
+    UnsupportedVersion(u32),
+    MissingField(&'static str),
+    InvalidLine { line: usize, text: String },
+    /// The recorded parameters do not make a valid board.
+    InvalidBoard(Box<Error>),
+    /// The `mask` rows do not match the recorded size.
+    MaskMismatch { width: usize, height: usize },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "cannot access replay file: {}", e),
+            ReplayError::BadHeader => write!(f, "not a minesweeper replay (missing '{}' header)", MAGIC),
+            ReplayError::UnsupportedVersion(v) => write!(f, "unsupported replay version {} (expected {})", v, VERSION),
+            ReplayError::MissingField(name) => write!(f, "replay is missing the '{}' field", name),
+            ReplayError::InvalidLine { line, text } => write!(f, "invalid replay line {}: '{}'", line, text),
+            ReplayError::InvalidBoard(e) => write!(f, "replay describes an invalid board: {}", e),
+            ReplayError::MaskMismatch { width, height } => write!(f, "replay mask rows do not match the {}x{} board", width, height),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::Io(e) => Some(e),
+            ReplayError::InvalidBoard(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(e: io::Error) -> Self { ReplayError::Io(e) }
+}
+
+impl Replay {
+    /// Start an empty recording for a board that has not been played yet.
+    pub fn new(board: &Board) -> Self {
+        Self { width: board.width(), height: board.height(), mines: board.mines(), seed: board.seed(), rng: board.rng(), no_guess: board.no_guess(), topology: board.topology(), wrap: board.wrap(), question_marks: board.question_marks(), mine_hunt: board.mine_hunt(), mask: board.mask().cloned(), events: Vec::new() }
+    }
+
+    pub fn push(&mut self, at: Duration, step: Step) {
+        self.events.push(Event { at, step });
+    }
+
+    /// A fresh board with the recorded parameters.
+    pub fn board(&self) -> Result<Board, ReplayError> {
+        let board = match &self.mask {
+            Some(mask) => Board::from_mask(mask.clone(), self.mines, self.seed),
+            None => Board::new(self.width, self.height, self.mines, self.seed),
+        };
+        let board = board.and_then(|b| b.with_topology(self.topology)?.with_wrap(self.wrap)).map_err(|e| ReplayError::InvalidBoard(Box::new(e)))?;
+        Ok(board.with_rng(self.rng).with_no_guess(self.no_guess).with_question_marks(self.question_marks).with_mine_hunt(self.mine_hunt))
+    }
+
+    pub fn encode(&self) -> String {
+        let mut s = String::new();
+        let _ = writeln!(s, "{} {}", MAGIC, VERSION);
+        let _ = writeln!(s, "size {} {}", self.width, self.height);
+        let _ = writeln!(s, "mines {}", self.mines);
+        let _ = writeln!(s, "seed {}", self.seed);
+        let _ = writeln!(s, "rng {}", self.rng);
+        let _ = writeln!(s, "no_guess {}", self.no_guess);
+        let _ = writeln!(s, "topology {}", self.topology);
+        let _ = writeln!(s, "wrap {}", self.wrap);
+        let _ = writeln!(s, "question_marks {}", self.question_marks);
+        let _ = writeln!(s, "mine_hunt {}", self.mine_hunt);
+        if let Some(mask) = &self.mask {
+            for y in 0..mask.height() {
+                let row: String = (0..mask.width()).map(|x| if mask.playable(x, y) { '#' } else { '-' }).collect();
+                let _ = writeln!(s, "mask {}", row);
+            }
+        }
+        s.push_str("events\n");
+        for e in &self.events {
+            let _ = write!(s, "{}", e.at.as_millis());
+            let _ = match e.step {
+                Step::Move(Action::Reveal(x, y)) => writeln!(s, " reveal {} {}", x, y),
+                Step::Move(Action::ToggleFlag(x, y)) => writeln!(s, " flag {} {}", x, y),
+                Step::Move(Action::Chord(x, y)) => writeln!(s, " chord {} {}", x, y),
+                Step::Undo => writeln!(s, " undo"),
+                Step::Redo => writeln!(s, " redo"),
+            };
+        }
+        s
+    }
+
+    pub fn decode(text: &str) -> Result<Self, ReplayError> {
+        let mut lines = text.lines().enumerate();
+        let (_, header) = lines.next().ok_or(ReplayError::BadHeader)?;
+        let mut hp = header.split_whitespace();
+        if hp.next() != Some(MAGIC) { return Err(ReplayError::BadHeader); }
+        let version: u32 = hp.next().and_then(|v| v.parse().ok()).ok_or(ReplayError::BadHeader)?;
+        if version != VERSION { return Err(ReplayError::UnsupportedVersion(version)); }
+
+        let (mut size, mut mines, mut seed, mut no_guess, mut topology, mut wrap) = (None, None, None, false, Topology::Square, false);
+        let (mut question_marks, mut mine_hunt) = (false, false);
+        let mut rng = Algorithm::default();
+        let mut mask_rows: Vec<String> = Vec::new();
+        for (n, line) in lines.by_ref() {
+            let invalid = || ReplayError::InvalidLine { line: n + 1, text: line.to_string() };
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                [] => continue,
+                ["events"] => break,
+                ["size", w, h] => size = Some((w.parse().map_err(|_| invalid())?, h.parse().map_err(|_| invalid())?)),
+                ["mines", m] => mines = Some(m.parse().map_err(|_| invalid())?),
+                ["seed", s] => seed = Some(s.parse().map_err(|_| invalid())?),
+                ["rng", v] => rng = v.parse().map_err(|_| invalid())?,
+                ["no_guess", v] => no_guess = v.parse().map_err(|_| invalid())?,
+                ["topology", v] => topology = v.parse().map_err(|_| invalid())?,
+                ["wrap", v] => wrap = v.parse().map_err(|_| invalid())?,
+                ["question_marks", v] => question_marks = v.parse().map_err(|_| invalid())?,
+                ["mine_hunt", v] => mine_hunt = v.parse().map_err(|_| invalid())?,
+                ["mask", row] if row.chars().all(|c| c == '#' || c == '-') => mask_rows.push(row.to_string()),
+                ["mask", ..] => return Err(invalid()),
+                _ => {}
+            }
+        }
+        let (width, height) = size.ok_or(ReplayError::MissingField("size"))?;
+        let mines = mines.ok_or(ReplayError::MissingField("mines"))?;
+        let seed = seed.ok_or(ReplayError::MissingField("seed"))?;
+        let mask = if mask_rows.is_empty() { None } else {
+            let bad = || ReplayError::MaskMismatch { width, height };
+            if mask_rows.len() != height || mask_rows.iter().any(|r| r.len() != width) { return Err(bad()); }
+            Some(Mask::new(width, height, mask_rows.concat().chars().map(|c| c == '#').collect()).ok_or_else(bad)?)
+        };
+
+        let mut events = Vec::new();
+        for (n, line) in lines {
+            let invalid = || ReplayError::InvalidLine { line: n + 1, text: line.to_string() };
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.is_empty() { continue; }
+            let at = Duration::from_millis(parts[0].parse().map_err(|_| invalid())?);
+            let coord = |i: usize| parts.get(i).and_then(|v| v.parse::<usize>().ok()).ok_or_else(invalid);
+            let step = match parts.get(1).copied() {
+                Some("reveal") => Step::Move(Action::Reveal(coord(2)?, coord(3)?)),
+                Some("flag") => Step::Move(Action::ToggleFlag(coord(2)?, coord(3)?)),
+                Some("chord") => Step::Move(Action::Chord(coord(2)?, coord(3)?)),
+                Some("undo") => Step::Undo,
+                Some("redo") => Step::Redo,
+                _ => return Err(invalid()),
+            };
+            events.push(Event { at, step });
+        }
+        Ok(Self { width, height, mines, seed, rng, no_guess, topology, wrap, question_marks, mine_hunt, mask, events })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ReplayError> {
+        fs::write(path, self.encode())?;
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> Result<Self, ReplayError> {
+        Self::decode(&fs::read_to_string(path)?)
+    }
+}
+
+/// Apply a recorded step to a board.
+pub fn apply(board: &mut Board, step: Step) {
+    match step {
+        Step::Move(Action::Reveal(x, y)) => { let _ = board.reveal(x, y); }
+        Step::Move(Action::ToggleFlag(x, y)) => { let _ = board.toggle_flag(x, y); }
+        Step::Move(Action::Chord(x, y)) => { let _ = board.chord(x, y); }
+        Step::Undo => { let _ = board.undo(); }
+        Step::Redo => { let _ = board.redo(); }
+    }
+}
+
+/// Re-drives a `Board` through a replay one event at a time.
+pub struct Player {
+    replay: Replay,
+    board: Board,
+    pos: usize,
+}
+
+impl Player {
+    pub fn new(replay: Replay) -> Result<Self, ReplayError> {
+        let board = replay.board()?;
+        Ok(Self { replay, board, pos: 0 })
+    }
+
+    /// Apply the next event, returning it, or `None` once the replay is exhausted.
+    pub fn step(&mut self) -> Option<Event> {
+        let event = *self.replay.events.get(self.pos)?;
+        apply(&mut self.board, event.step);
+        self.pos += 1;
+        Some(event)
+    }
+
+    /// Recorded time between the last applied event and the next one.
+    pub fn next_delay(&self) -> Option<Duration> {
+        let next = self.replay.events.get(self.pos)?;
+        let prev = if self.pos == 0 { Duration::ZERO } else { self.replay.events[self.pos - 1].at };
+        Some(next.at.saturating_sub(prev))
+    }
+
+    pub fn board(&self) -> &Board { &self.board }
+    pub fn replay(&self) -> &Replay { &self.replay }
+    pub fn position(&self) -> usize { self.pos }
+    pub fn len(&self) -> usize { self.replay.events.len() }
+    pub fn is_empty(&self) -> bool { self.replay.events.is_empty() }
+    pub fn finished(&self) -> bool { self.pos >= self.replay.events.len() }
+}
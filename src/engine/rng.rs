@@ -0,0 +1,246 @@
+//! Random mine placement and the portable seed string.
+//!
+//! A mine layout is a fixed function of the generator, the seed, the board
+//! parameters and the first click, so a board can be shared between versions:
+//!
+//! 1. List the playable cells in row-major order (`y * width + x`), leaving out
+//!    the first click (in no-guess mode also its neighbors, when enough safe
+//!    cells remain).
+//! 2. Shuffle the list with Fisher–Yates: for `i` from `len - 1` down to 1, swap
+//!    item `i` with item `j = (next_u64() >> 1) % (i + 1)` (see `below`).
+//! 3. The first `mines` cells of the list are mines.
+//!
+//! No-guess boards repeat steps 2–3 on the same list and generator stream, up to
+//! 500 times and 2^21 cells' worth of solver passes, until logic alone clears the
+//! board from the first click; if none does, the layout that left the fewest cells
+//! covered is used. Which layout passes depends on the solver's rules, so this part
+//! is versioned (`NO_GUESS_VERSION`) instead of specified.
+//!
+//! A seed string pins all of this down in one line:
+//!
+//! ```text
+//! ALG:SEED:WIDTHxHEIGHT:MINES[:ngN][:hex][:wrap][:X,Y]
+//! xs64:12345:30x16:99
+//! sm64:7:16x16:40:ng1:8,8
+//! ```
+//!
+//! `ALG` is a generator name (`xs64` or `sm64`), `SEED` a non-zero decimal seed,
+//! `ngN` asks for a no-guess board made by version `N` of the no-guess generator
+//! (`ng` alone means `ng1`), `hex` and `wrap` set the topology and `X,Y` is the
+//! first click (1-based). Shaped boards cannot be written as a seed string.
+//!
+//! Boards without `ng` are portable to any version. A build refuses `ngN` strings
+//! for versions it does not implement rather than deal a different board.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::stats::Clicks;
+use super::{Board, Error, Topology};
+
+/// Version of the no-guess generation: solver rules plus attempt and work budgets.
+/// Bump it whenever a change could pick a different layout for the same seed.
+pub const NO_GUESS_VERSION: u32 = 1;
+
+/// A source of random numbers for mine placement. Generators only supply the raw
+/// stream; turning it into indices is fixed by `below`.
+pub trait MineRng {
+    fn next_u64(&mut self) -> u64;
+}
+
+/// A number in `0..n`: `(next_u64() >> 1) % n`. This mapping is part of the layout
+/// definition, so it is the same for every generator.
+pub fn below(rng: &mut dyn MineRng, n: usize) -> usize {
+    ((rng.next_u64() >> 1) % n as u64) as usize
+}
+
+/// Marsaglia's xorshift64 (shifts 13, 7, 17); a zero seed is treated as 1.
+#[derive(Clone, Debug)]
+pub struct XorShift64 { state: u64 }
+
+impl XorShift64 {
+    pub fn new(seed: u64) -> Self { Self { state: seed.max(1) } }
+}
+
+impl MineRng for XorShift64 {
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+}
+
+/// Vigna's SplitMix64.
+#[derive(Clone, Debug)]
+pub struct SplitMix64 { state: u64 }
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self { Self { state: seed } }
+}
+
+impl MineRng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// The built-in generators, by their seed-string names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// `xs64`: the generator every board used before others were added.
+    #[default]
+    XorShift64,
+    /// `sm64`
+    SplitMix64,
+}
+
+impl Algorithm {
+    pub fn name(self) -> &'static str {
+        match self { Algorithm::XorShift64 => "xs64", Algorithm::SplitMix64 => "sm64" }
+    }
+
+    /// A generator of this kind started from `seed`.
+    pub fn generator(self, seed: u64) -> Box<dyn MineRng> {
+        match self {
+            Algorithm::XorShift64 => Box::new(XorShift64::new(seed)),
+            Algorithm::SplitMix64 => Box::new(SplitMix64::new(seed)),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name()) }
+}
+
+impl FromStr for Algorithm {
+    type Err = SeedError;
+    fn from_str(s: &str) -> Result<Self, SeedError> {
+        match s {
+            "xs64" => Ok(Algorithm::XorShift64),
+            "sm64" => Ok(Algorithm::SplitMix64),
+            _ => Err(SeedError::UnknownAlgorithm(s.to_string())),
+        }
+    }
+}
+
+/// Shuffle `items` in place as described in the module docs.
+pub fn shuffle<T>(items: &mut [T], rng: &mut dyn MineRng) {
+    for i in (1..items.len()).rev() {
+        let j = below(rng, i + 1);
+        items.swap(i, j);
+    }
+}
+
+#[derive(Debug)]
+pub enum SeedError {
+    /// Not `ALG:SEED:WIDTHxHEIGHT:MINES[:options]`.
+    Format(String),
+    UnknownAlgorithm(String),
+    InvalidField { field: &'static str, value: String },
+    /// An `ngN` option for a no-guess generator this build does not have.
+    UnsupportedNoGuess(u32),
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::Format(s) => write!(f, "seed string '{}' is not ALG:SEED:WIDTHxHEIGHT:MINES[:options]", s),
+            SeedError::UnknownAlgorithm(a) => write!(f, "unknown generator '{}' (expected xs64 or sm64)", a),
+            SeedError::InvalidField { field, value } => write!(f, "invalid {} '{}' in seed string", field, value),
+            SeedError::UnsupportedNoGuess(v) => write!(f, "no-guess boards of version {} cannot be rebuilt (this version makes ng{})", v, NO_GUESS_VERSION),
+        }
+    }
+}
+
+impl std::error::Error for SeedError {}
+
+/// Everything needed to rebuild a board: the parsed form of a seed string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeedSpec {
+    pub algorithm: Algorithm,
+    pub seed: u64,
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub no_guess: bool,
+    pub topology: Topology,
+    pub wrap: bool,
+    /// 0-based first click; the layout depends on it.
+    pub first_click: Option<(usize, usize)>,
+}
+
+impl SeedSpec {
+    /// The seed string of a board, with the first click once mines are placed;
+    /// `None` for shaped boards.
+    pub fn of(board: &Board) -> Option<Self> {
+        if board.mask().is_some() { return None; }
+        Some(Self {
+            algorithm: board.rng(), seed: board.seed(), width: board.width(), height: board.height(), mines: board.mines(),
+            no_guess: board.no_guess(), topology: board.topology(), wrap: board.wrap(), first_click: board.first_click(),
+        })
+    }
+
+    /// Build the board, already opened at the first click if the spec has one; that
+    /// click is not counted as one of the player's.
+    pub fn board(&self) -> Result<Board, Error> {
+        let mut board = Board::new(self.width, self.height, self.mines, self.seed)?
+            .with_rng(self.algorithm).with_no_guess(self.no_guess).with_topology(self.topology)?.with_wrap(self.wrap)?;
+        if let Some((x, y)) = self.first_click {
+            board.try_reveal(x, y)?;
+            board.clicks = Clicks::default();
+        }
+        Ok(board)
+    }
+}
+
+impl fmt::Display for SeedSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}x{}:{}", self.algorithm, self.seed, self.width, self.height, self.mines)?;
+        if self.no_guess { write!(f, ":ng{}", NO_GUESS_VERSION)?; }
+        if self.topology == Topology::Hex { f.write_str(":hex")?; }
+        if self.wrap { f.write_str(":wrap")?; }
+        if let Some((x, y)) = self.first_click { write!(f, ":{},{}", x + 1, y + 1)?; }
+        Ok(())
+    }
+}
+
+impl FromStr for SeedSpec {
+    type Err = SeedError;
+    fn from_str(s: &str) -> Result<Self, SeedError> {
+        let fields: Vec<&str> = s.trim().split(':').collect();
+        let [alg, seed, size, mines, options @ ..] = &fields[..] else { return Err(SeedError::Format(s.to_string())) };
+        let invalid = |field, value: &str| SeedError::InvalidField { field, value: value.to_string() };
+        let algorithm = alg.parse()?;
+        let seed = seed.parse().ok().filter(|&n| n != 0).ok_or_else(|| invalid("seed", seed))?;
+        let (width, height) = size.split_once('x')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+            .ok_or_else(|| invalid("size", size))?;
+        let mines = mines.parse().map_err(|_| invalid("mine count", mines))?;
+        let mut spec = SeedSpec { algorithm, seed, width, height, mines, no_guess: false, topology: Topology::Square, wrap: false, first_click: None };
+        for &opt in options {
+            match opt {
+                "ng" => spec.no_guess = true,
+                _ if opt.starts_with("ng") => {
+                    let version: u32 = opt[2..].parse().map_err(|_| invalid("option", opt))?;
+                    if version != NO_GUESS_VERSION { return Err(SeedError::UnsupportedNoGuess(version)); }
+                    spec.no_guess = true;
+                }
+                "hex" => spec.topology = Topology::Hex,
+                "wrap" => spec.wrap = true,
+                _ => {
+                    let click = opt.split_once(',')
+                        .and_then(|(x, y)| Some((x.parse::<usize>().ok()?.checked_sub(1)?, y.parse::<usize>().ok()?.checked_sub(1)?)));
+                    spec.first_click = Some(click.ok_or_else(|| invalid("option", opt))?);
+                }
+            }
+        }
+        Ok(spec)
+    }
+}
@@ -0,0 +1,300 @@
+//! Versioned plain-text save format for a full `Board`.
+//!
+//! ```text
+//! minesweeper-save 1
+//! size 9 9
+//! mines 10
+//! seed 12345
+//! rng xs64
+//! no_guess false
+//! topology square
+//! wrap false
+//! question_marks false
+//! mine_hunt false
+//! initialized true
+//! alive true
+//! won false
+//! elapsed_ms 73120
+//! clicks 31 6 4 2
+//! first_click 4 4
+//! grid
+//! 0001.....
+//! ...
+//! ```
+//!
+//! Grid characters: `.` covered, `*` covered mine, `f` flagged, `F` flagged mine,
+//! `q` question mark, `Q` question mark on a mine, `0`-`8` revealed with that many
+//! adjacent mines, `X` revealed mine, `-` a hole in a shaped (masked) board.
+//! Revealed numbers are checked against the mine layout when loading. A missing
+//! `topology` line means a square grid, a missing `wrap` line a board with edges and
+//! a missing `question_marks` line a flags-only marker cycle. `mine_hunt` boards (the
+//! Flags variant) keep revealed mines while alive and are never won; a missing line
+//! means a normal game. `clicks` holds the
+//! left, right, chord and wasted click counts (zero when missing). `rng` names the
+//! mine generator (`xs64` when missing) and `first_click` the 0-based cell the
+//! mines were placed around, so `SeedSpec::of` works on loaded games too.
+
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use super::mask::Mask;
+use super::rng::Algorithm;
+use super::stats::Clicks;
+use super::{idx, Board, Cell, Topology};
+
+pub const MAGIC: &str = "minesweeper-save";
+pub const VERSION: u32 = 1;
+// Grid character for a cell outside the board's mask.
+const HOLE: char = '-';
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    BadHeader,
+    UnsupportedVersion(u32),
+    MissingField(&'static str),
+    InvalidValue { field: &'static str, value: String },
+    RowCount { expected: usize, found: usize },
+    RowLength { row: usize, expected: usize, found: usize },
+    InvalidCell { x: usize, y: usize, ch: char },
+    MineCountMismatch { expected: usize, found: usize },
+    AdjacencyMismatch { x: usize, y: usize, stored: u8, actual: u8 },
+    Inconsistent(&'static str),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "cannot access save file: {}", e),
+            SaveError::BadHeader => write!(f, "not a minesweeper save file (missing '{}' header)", MAGIC),
+            SaveError::UnsupportedVersion(v) => write!(f, "unsupported save version {} (expected {})", v, VERSION),
+            SaveError::MissingField(name) => write!(f, "save file is missing the '{}' field", name),
+            SaveError::InvalidValue { field, value } => write!(f, "invalid value '{}' for '{}'", value, field),
+            SaveError::RowCount { expected, found } => write!(f, "grid has {} rows, expected {}", found, expected),
+            SaveError::RowLength { row, expected, found } => write!(f, "grid row {} has {} cells, expected {}", row + 1, found, expected),
+            SaveError::InvalidCell { x, y, ch } => write!(f, "invalid cell '{}' at column {}, row {}", ch, x + 1, y + 1),
+            SaveError::MineCountMismatch { expected, found } => write!(f, "header says {} mines but the grid has {}", expected, found),
+            SaveError::AdjacencyMismatch { x, y, stored, actual } => write!(
+                f, "cell at column {}, row {} shows {} but has {} adjacent mines", x + 1, y + 1, stored, actual
+            ),
+            SaveError::Inconsistent(what) => write!(f, "inconsistent save file: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self { SaveError::Io(e) => Some(e), _ => None }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self { SaveError::Io(e) }
+}
+
+/// Serialize the board (without undo history) to the save format.
+pub fn encode(board: &Board) -> String {
+    let mut s = String::new();
+    let _ = writeln!(s, "{} {}", MAGIC, VERSION);
+    let _ = writeln!(s, "size {} {}", board.width, board.height);
+    let _ = writeln!(s, "mines {}", board.mines);
+    let _ = writeln!(s, "seed {}", board.seed);
+    let _ = writeln!(s, "rng {}", board.rng);
+    let _ = writeln!(s, "no_guess {}", board.no_guess);
+    let _ = writeln!(s, "topology {}", board.topology);
+    let _ = writeln!(s, "wrap {}", board.wrap);
+    let _ = writeln!(s, "question_marks {}", board.question_marks);
+    let _ = writeln!(s, "mine_hunt {}", board.hunt);
+    let _ = writeln!(s, "initialized {}", board.initialized);
+    let _ = writeln!(s, "alive {}", board.alive);
+    let _ = writeln!(s, "won {}", board.won);
+    let _ = writeln!(s, "elapsed_ms {}", board.elapsed().as_millis());
+    let c = &board.clicks;
+    let _ = writeln!(s, "clicks {} {} {} {}", c.left, c.right, c.chord, c.wasted);
+    if let Some((x, y)) = board.first_click() { let _ = writeln!(s, "first_click {} {}", x, y); }
+    s.push_str("grid\n");
+    for y in 0..board.height {
+        for x in 0..board.width {
+            s.push(if board.playable(x, y) { cell_char(&board.cells[idx(board.width, x, y)]) } else { HOLE });
+        }
+        s.push('\n');
+    }
+    s
+}
+
+/// Parse and validate a save file produced by `encode`.
+pub fn decode(text: &str) -> Result<Board, SaveError> {
+    let mut lines = text.lines();
+    let header = lines.next().ok_or(SaveError::BadHeader)?;
+    let mut hp = header.split_whitespace();
+    if hp.next() != Some(MAGIC) { return Err(SaveError::BadHeader); }
+    let version = parse_field::<u32>("version", hp.next())?;
+    if version != VERSION { return Err(SaveError::UnsupportedVersion(version)); }
+
+    let (mut size, mut mines, mut seed, mut initialized, mut alive, mut won) = (None, None, None, None, None, None);
+    let mut no_guess = false;
+    let mut topology = Topology::Square;
+    let mut wrap = false;
+    let mut question_marks = false;
+    let mut hunt = false;
+    let mut elapsed_ms = 0u64;
+    let mut clicks = Clicks::default();
+    let mut rng = Algorithm::default();
+    let mut first_click = None;
+    for line in lines.by_ref() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            None => continue,
+            Some("grid") => break,
+            Some("size") => size = Some((parse_field::<usize>("size", parts.next())?, parse_field::<usize>("size", parts.next())?)),
+            Some("mines") => mines = Some(parse_field::<usize>("mines", parts.next())?),
+            Some("seed") => seed = Some(parse_field::<u64>("seed", parts.next())?),
+            Some("rng") => rng = parse_field::<Algorithm>("rng", parts.next())?,
+            Some("first_click") => first_click = Some((parse_field::<usize>("first_click", parts.next())?, parse_field::<usize>("first_click", parts.next())?)),
+            Some("no_guess") => no_guess = parse_field::<bool>("no_guess", parts.next())?,
+            Some("topology") => topology = parse_field::<Topology>("topology", parts.next())?,
+            Some("wrap") => wrap = parse_field::<bool>("wrap", parts.next())?,
+            Some("question_marks") => question_marks = parse_field::<bool>("question_marks", parts.next())?,
+            Some("mine_hunt") => hunt = parse_field::<bool>("mine_hunt", parts.next())?,
+            Some("initialized") => initialized = Some(parse_field::<bool>("initialized", parts.next())?),
+            Some("alive") => alive = Some(parse_field::<bool>("alive", parts.next())?),
+            Some("elapsed_ms") => elapsed_ms = parse_field::<u64>("elapsed_ms", parts.next())?,
+            Some("clicks") => {
+                let mut next = || parse_field::<usize>("clicks", parts.next());
+                clicks = Clicks { left: next()?, right: next()?, chord: next()?, wasted: next()? };
+                if clicks.wasted > clicks.total() { return Err(SaveError::Inconsistent("more wasted clicks than clicks")); }
+            }
+            Some("won") => won = Some(parse_field::<bool>("won", parts.next())?),
+            // Unknown keys are ignored so newer writers stay readable.
+            Some(_) => {}
+        }
+    }
+    let (width, height) = size.ok_or(SaveError::MissingField("size"))?;
+    let mines = mines.ok_or(SaveError::MissingField("mines"))?;
+    let seed = seed.ok_or(SaveError::MissingField("seed"))?;
+    let initialized = initialized.ok_or(SaveError::MissingField("initialized"))?;
+    let alive = alive.ok_or(SaveError::MissingField("alive"))?;
+    let won = won.ok_or(SaveError::MissingField("won"))?;
+
+    let rows: Vec<&str> = lines.filter(|l| !l.is_empty()).collect();
+    if rows.len() != height {
+        return Err(SaveError::RowCount { expected: height, found: rows.len() });
+    }
+    // Check the rows against the header before trusting it with an allocation.
+    for (y, row) in rows.iter().enumerate() {
+        let found = row.chars().count();
+        if found != width { return Err(SaveError::RowLength { row: y, expected: width, found }); }
+    }
+    let total = width.checked_mul(height).ok_or_else(|| SaveError::InvalidValue { field: "size", value: format!("{} {}", width, height) })?;
+    let mut cells = Vec::with_capacity(total);
+    let mut stored = Vec::with_capacity(total);
+    let mut playable = Vec::with_capacity(total);
+    for (y, row) in rows.iter().enumerate() {
+        for (x, ch) in row.chars().enumerate() {
+            playable.push(ch != HOLE);
+            let (cell, shown) = if ch == HOLE { (Cell::default(), None) } else { parse_cell(ch).ok_or(SaveError::InvalidCell { x, y, ch })? };
+            cells.push(cell);
+            stored.push(shown);
+        }
+    }
+
+    let invalid_size = || SaveError::InvalidValue { field: "size/mines", value: format!("{}x{}/{}", width, height, mines) };
+    let board = if playable.iter().all(|&p| p) {
+        Board::new(width, height, mines, seed).map_err(|_| invalid_size())?
+    } else {
+        let mask = Mask::new(width, height, playable).ok_or_else(invalid_size)?;
+        Board::from_mask(mask, mines, seed).map_err(|_| invalid_size())?
+    };
+    let mut board = board.with_topology(topology).and_then(|b| b.with_wrap(wrap)).map_err(|_| invalid_size())?.with_question_marks(question_marks).with_mine_hunt(hunt);
+    board.cells = cells;
+
+    let found = board.cells.iter().filter(|c| c.is_mine).count();
+    if initialized && found != mines { return Err(SaveError::MineCountMismatch { expected: mines, found }); }
+    if !initialized && (found != 0 || board.cells.iter().any(|c| c.revealed)) {
+        return Err(SaveError::Inconsistent("uninitialized board has mines or revealed cells"));
+    }
+
+    board.compute_adjacency();
+    for y in 0..height {
+        for x in 0..width {
+            let i = idx(width, x, y);
+            if let Some(n) = stored[i] {
+                if n != board.cells[i].adjacent {
+                    return Err(SaveError::AdjacencyMismatch { x, y, stored: n, actual: board.cells[i].adjacent });
+                }
+            }
+        }
+    }
+    // Outside mine hunting a revealed mine is only possible as the losing move.
+    if alive && !hunt && board.cells.iter().any(|c| c.revealed && c.is_mine) {
+        return Err(SaveError::Inconsistent("revealed mine on a board that is still alive"));
+    }
+
+    let revealed_safe = board.cells.iter().filter(|c| c.revealed && !c.is_mine).count();
+    board.remaining_safe -= revealed_safe;
+    if won && (hunt || !alive || board.remaining_safe != 0) {
+        return Err(SaveError::Inconsistent("marked as won but safe cells remain covered"));
+    }
+    if alive && !won && !hunt && initialized && board.remaining_safe == 0 {
+        return Err(SaveError::Inconsistent("all safe cells revealed but not marked as won"));
+    }
+    board.initialized = initialized;
+    board.no_guess = no_guess;
+    board.alive = alive;
+    board.won = won;
+    board.clicks = clicks;
+    board.rng = rng;
+    board.first_click = first_click.filter(|&(x, y)| initialized && x < width && y < height);
+    board.timer_banked = Duration::from_millis(elapsed_ms);
+    board.timer_restored = initialized;
+    board.sync_timer();
+    Ok(board)
+}
+
+pub fn write(board: &Board, path: &Path) -> Result<(), SaveError> {
+    fs::write(path, encode(board))?;
+    Ok(())
+}
+
+pub fn read(path: &Path) -> Result<Board, SaveError> {
+    decode(&fs::read_to_string(path)?)
+}
+
+fn parse_field<T: std::str::FromStr>(field: &'static str, value: Option<&str>) -> Result<T, SaveError> {
+    let value = value.ok_or(SaveError::MissingField(field))?;
+    value.parse().map_err(|_| SaveError::InvalidValue { field, value: value.to_string() })
+}
+
+fn cell_char(c: &Cell) -> char {
+    match (c.revealed, c.flagged, c.question, c.is_mine) {
+        (true, _, _, true) => 'X',
+        (true, _, _, false) => char::from_digit(c.adjacent as u32, 10).unwrap_or('0'),
+        (false, true, _, true) => 'F',
+        (false, true, _, false) => 'f',
+        (false, false, true, true) => 'Q',
+        (false, false, true, false) => 'q',
+        (false, false, false, true) => '*',
+        (false, false, false, false) => '.',
+    }
+}
+
+// Returns the cell and, for revealed numbers, the adjacency stored in the file.
+fn parse_cell(ch: char) -> Option<(Cell, Option<u8>)> {
+    let mut c = Cell::default();
+    let mut shown = None;
+    match ch {
+        '.' => {}
+        '*' => c.is_mine = true,
+        'f' => c.flagged = true,
+        'F' => { c.flagged = true; c.is_mine = true; }
+        'q' => c.question = true,
+        'Q' => { c.question = true; c.is_mine = true; }
+        'X' => { c.revealed = true; c.is_mine = true; }
+        '0'..='8' => { c.revealed = true; shown = ch.to_digit(10).map(|d| d as u8); }
+        _ => return None,
+    }
+    Some((c, shown))
+}
@@ -0,0 +1,635 @@
+//! Deductions from the player-visible state of a `Board`.
+//!
+//! The solver only looks at what the player sees: revealed numbers, flags (treated
+//! as mines) and, in mine hunting, revealed mines. It never peeks at covered cells.
+//! It applies, in order of cost:
+//!
+//! 1. single-cell rules: a number already touching all its mines clears the rest,
+//!    a number with as many covered neighbors as missing mines flags them all;
+//! 2. subset rules: if one number's covered neighbors are a subset of another's,
+//!    the difference holds exactly the difference in missing mines;
+//! 3. full enumeration of every consistent mine arrangement over each connected
+//!    frontier component, combined with the global mine count.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use super::rng::{MineRng, XorShift64};
+use super::{Board, Topology};
+
+// Frontier components whose enumeration needs more search nodes than this are
+// skipped by `solve` and approximated by sampling in `probabilities`.
+const NODE_BUDGET: usize = 1 << 20;
+const SAMPLES: usize = 400;
+const SAMPLE_BUDGET: usize = 1 << 14;
+
+/// Cells the visible information proves safe or mined, as `(x, y)` pairs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Deductions {
+    pub safe: BTreeSet<(usize, usize)>,
+    pub mines: BTreeSet<(usize, usize)>,
+}
+
+impl Deductions {
+    pub fn is_empty(&self) -> bool { self.safe.is_empty() && self.mines.is_empty() }
+}
+
+/// Deduce every cell that is certainly safe or certainly a mine. Cells that are
+/// already flagged are not reported again.
+pub fn solve(board: &Board) -> Deductions {
+    let mut view = View::new(board);
+    loop {
+        if view.apply_simple_rules() { continue; }
+        if view.apply_enumeration() { continue; }
+        break;
+    }
+    let w = view.width;
+    let mut out = Deductions::default();
+    for (i, k) in view.known.iter().enumerate() {
+        if view.visible[i] != Known::Unknown { continue; }
+        match k {
+            Known::Safe => { out.safe.insert((i % w, i / w)); }
+            Known::Mine => { out.mines.insert((i % w, i / w)); }
+            Known::Unknown => {}
+        }
+    }
+    out
+}
+
+/// Chance that each covered, unflagged cell holds a mine, given the visible
+/// numbers, flags and the total mine count.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Probabilities {
+    width: usize,
+    cells: Vec<Option<f64>>,
+    exact: bool,
+}
+
+impl Probabilities {
+    /// `None` for revealed and flagged cells.
+    pub fn get(&self, x: usize, y: usize) -> Option<f64> {
+        if x >= self.width { return None; }
+        self.cells.get(y * self.width + x).copied().flatten()
+    }
+
+    /// False when part of the frontier was too large to enumerate and was sampled.
+    pub fn exact(&self) -> bool { self.exact }
+
+    /// The covered cell least likely to be a mine (first in row-major order on ties).
+    pub fn safest(&self) -> Option<((usize, usize), f64)> {
+        let mut best: Option<((usize, usize), f64)> = None;
+        for (i, p) in self.cells.iter().enumerate() {
+            let Some(p) = *p else { continue };
+            if best.is_none_or(|(_, b)| p < b) { best = Some(((i % self.width, i / self.width), p)); }
+        }
+        best
+    }
+
+    /// Grid of percentages for covered cells, numbers for revealed ones and `F` for flags.
+    pub fn render(&self, board: &Board, one_based: bool) -> String {
+        let mut s = String::from("    ");
+        for x in 0..board.width() {
+            let _ = write!(s, "{:>3} ", if one_based { x + 1 } else { x });
+        }
+        s.push('\n');
+        s.push_str("   ");
+        s.push_str(&"-".repeat(board.width() * 4 + 1));
+        s.push('\n');
+        for y in 0..board.height() {
+            let _ = write!(s, "{:>2} | ", if one_based { y + 1 } else { y });
+            if board.topology() == Topology::Hex && y % 2 == 1 { s.push_str("  "); }
+            for x in 0..board.width() {
+                let c = board.cell(x, y).expect("in bounds");
+                let _ = match self.get(x, y) {
+                    Some(p) => write!(s, "{:>3} ", (p * 100.0).round() as u32),
+                    None if !board.playable(x, y) => write!(s, "{:>3} ", ""),
+                    None if c.flagged() => write!(s, "{:>3} ", "F"),
+                    None if c.adjacent() == 0 => write!(s, "{:>3} ", ""),
+                    None => write!(s, "{:>3} ", c.adjacent()),
+                };
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+/// Mine probability of every covered cell. Frontier components are enumerated
+/// exactly and weighted by how many ways the remaining mines fit in the cells no
+/// number touches; components too large to enumerate are approximated by sampling.
+pub fn probabilities(board: &Board) -> Probabilities {
+    let mut view = View::new(board);
+    while view.apply_simple_rules() {}
+    let mut comps = view.components();
+    let mut exact = true;
+    let mut rng = XorShift64::new(0x9E37_79B9_7F4A_7C15);
+    for c in comps.iter_mut() {
+        if c.by_mines.is_empty() { c.by_mines = sample(c, &mut rng); exact = false; }
+    }
+
+    let remaining = view.remaining_mines();
+    let unknown: Vec<usize> = (0..view.known.len()).filter(|&i| view.known[i] == Known::Unknown).collect();
+    let frontier: BTreeSet<usize> = comps.iter().flat_map(|c| c.cells.iter().copied()).collect();
+    let interior = unknown.len() - frontier.len();
+    let len = remaining.min(frontier.len()) + 1;
+
+    // Per-component weight by mine count, scaled so the largest is 1 (the factor cancels).
+    let dists: Vec<Vec<f64>> = comps.iter().map(|c| {
+        let max = c.by_mines.values().map(|(n, _)| *n).fold(0.0, f64::max);
+        let mut d = vec![0.0; len];
+        for (&k, (n, _)) in &c.by_mines {
+            if k < len && max > 0.0 { d[k] = n / max; }
+        }
+        d
+    }).collect();
+
+    // Ways to place the rest of the mines in the interior, relative to the best case.
+    let mut ln_fact = vec![0.0f64; interior + 1];
+    for i in 1..=interior { ln_fact[i] = ln_fact[i - 1] + (i as f64).ln(); }
+    let ln_w: Vec<f64> = (0..=remaining).map(|s| {
+        let k = remaining - s;
+        if k > interior { f64::NEG_INFINITY } else { ln_fact[interior] - ln_fact[k] - ln_fact[interior - k] }
+    }).collect();
+    let ln_max = ln_w.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let wt = |s: usize| if s <= remaining && ln_w[s].is_finite() { (ln_w[s] - ln_max).exp() } else { 0.0 };
+
+    let mut prefix = vec![unit(len)];
+    for d in &dists { prefix.push(convolve(prefix.last().expect("non-empty"), d)); }
+    let mut suffix = vec![unit(len)];
+    for d in dists.iter().rev() { suffix.push(convolve(suffix.last().expect("non-empty"), d)); }
+    suffix.reverse();
+    let all = &prefix[dists.len()];
+    let total: f64 = all.iter().enumerate().map(|(s, n)| n * wt(s)).sum();
+
+    let mut cells = vec![None; view.known.len()];
+    for (i, &k) in view.known.iter().enumerate() {
+        if view.visible[i] != Known::Unknown { continue; }
+        cells[i] = match k { Known::Mine => Some(1.0), Known::Safe => Some(0.0), Known::Unknown => None };
+    }
+    if !(total > 0.0 && total.is_finite()) {
+        // Contradictory flags: fall back to the uniform density.
+        let p = if unknown.is_empty() { 0.0 } else { (remaining as f64 / unknown.len() as f64).min(1.0) };
+        for &i in &unknown { cells[i] = Some(p); }
+        return Probabilities { width: view.width, cells, exact: false };
+    }
+
+    if interior > 0 {
+        let p: f64 = all.iter().enumerate()
+            .map(|(s, n)| n * wt(s) * (remaining.saturating_sub(s)) as f64 / interior as f64)
+            .sum::<f64>() / total;
+        for &i in unknown.iter().filter(|i| !frontier.contains(i)) { cells[i] = Some(p); }
+    }
+    for (ci, comp) in comps.iter().enumerate() {
+        let others = convolve(&prefix[ci], &suffix[ci + 1]);
+        let scale = comp.by_mines.values().map(|(n, _)| *n).fold(0.0, f64::max);
+        let mut acc = vec![0.0; comp.cells.len()];
+        let mut norm = 0.0;
+        for (&k, (n, per)) in &comp.by_mines {
+            let w: f64 = others.iter().enumerate().map(|(s, o)| o * wt(s + k)).sum();
+            norm += n / scale * w;
+            for (a, p) in acc.iter_mut().zip(per) { *a += p / scale * w; }
+        }
+        for (li, &cell) in comp.cells.iter().enumerate() {
+            cells[cell] = Some(if norm > 0.0 { acc[li] / norm } else { 0.0 });
+        }
+    }
+    Probabilities { width: view.width, cells, exact }
+}
+
+fn unit(len: usize) -> Vec<f64> {
+    let mut v = vec![0.0; len];
+    v[0] = 1.0;
+    v
+}
+
+// Polynomial product truncated to the length of `a`.
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; a.len()];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0.0 { continue; }
+        for (j, &y) in b.iter().enumerate() {
+            if i + j >= out.len() { break; }
+            out[i + j] += x * y;
+        }
+    }
+    out
+}
+
+/// What a hint suggests doing with its cell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HintKind {
+    /// The cell is certainly safe: reveal it.
+    Safe,
+    /// The cell is certainly a mine: flag it.
+    Mine,
+    /// No logical move exists; this is the lowest-risk cell, with its mine probability.
+    Guess(f64),
+}
+
+/// One suggested move at the 0-based cell (`x`, `y`), with an explanation in
+/// words. Cells named in `reason` are 1-based, as the CLI shows them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hint {
+    pub x: usize,
+    pub y: usize,
+    pub kind: HintKind,
+    pub reason: String,
+}
+
+/// Suggest one move, preferring the simplest explanation: a single number, then a
+/// pair of overlapping numbers, then full enumeration, and finally the safest guess.
+/// Returns `None` when the game is over or nothing is left to reveal.
+pub fn hint(board: &Board) -> Option<Hint> {
+    if !board.alive() || board.won() { return None; }
+    let view = View::new(board);
+    let w = view.width;
+    let at = |i: usize| format!("({},{})", i % w + 1, i / w + 1);
+    let number = |i: usize| format!("the {} at {}", view.numbers[i].unwrap_or(0), at(i));
+    let make = |cell: usize, kind: HintKind, reason: String| Some(Hint { x: cell % w, y: cell / w, kind, reason });
+    let cons = view.constraints();
+
+    for want_safe in [true, false] {
+        for c in &cons {
+            let flags = view.numbers[c.origin].unwrap_or(0) as usize - c.mines;
+            if want_safe && c.mines == 0 {
+                let why = match flags {
+                    0 => format!("{} has no mines around it", number(c.origin)),
+                    1 => format!("{} already touches a flag", number(c.origin)),
+                    n => format!("{} already touches {} flags", number(c.origin), n),
+                };
+                return make(c.cells[0], HintKind::Safe, format!("{}, so {} is safe", capitalize(&why), at(c.cells[0])));
+            }
+            if !want_safe && c.mines > 0 && c.mines == c.cells.len() {
+                let why = format!("{} needs {} more {} and has only {} covered {}", number(c.origin), c.mines, plural(c.mines, "mine"), c.cells.len(), plural(c.cells.len(), "neighbor"));
+                return make(c.cells[0], HintKind::Mine, format!("{}, so {} is a mine", capitalize(&why), at(c.cells[0])));
+            }
+        }
+    }
+
+    for want_safe in [true, false] {
+        for a in &cons {
+            for b in &cons {
+                if b.cells.len() <= a.cells.len() || b.mines < a.mines { continue; }
+                if !a.cells.iter().all(|c| b.cells.binary_search(c).is_ok()) { continue; }
+                let rest: Vec<usize> = b.cells.iter().copied().filter(|c| a.cells.binary_search(c).is_err()).collect();
+                let dm = b.mines - a.mines;
+                let shared = format!(
+                    "{} needs {} {} among covered cells that {} also touches",
+                    number(a.origin), a.mines, plural(a.mines, "mine"), number(b.origin)
+                );
+                if want_safe && dm == 0 {
+                    let why = format!("{}, and {} needs no more than that", shared, number(b.origin));
+                    return make(rest[0], HintKind::Safe, format!("{}, so {} is safe", capitalize(&why), at(rest[0])));
+                }
+                if !want_safe && dm > 0 && dm == rest.len() {
+                    let why = format!("{}; {} needs {} more and has exactly {} other covered {}", shared, number(b.origin), dm, dm, plural(dm, "neighbor"));
+                    return make(rest[0], HintKind::Mine, format!("{}, so {} is a mine", capitalize(&why), at(rest[0])));
+                }
+            }
+        }
+    }
+
+    let d = solve(board);
+    let pick = d.safe.iter().next().map(|&c| (c, HintKind::Safe)).or_else(|| d.mines.iter().next().map(|&c| (c, HintKind::Mine)));
+    if let Some(((x, y), kind)) = pick {
+        let i = y * w + x;
+        let touching: Vec<String> = view.neighbors[i].iter().filter(|&&n| view.numbers[n].is_some()).map(|&n| number(n)).collect();
+        let around = if touching.is_empty() { "the total mine count".to_string() } else { format!("{} and the numbers linked to {}", join(&touching), if touching.len() == 1 { "it" } else { "them" }) };
+        let verdict = if kind == HintKind::Safe { "never a mine, so it is safe" } else { "always a mine" };
+        return make(i, kind, format!("In every arrangement of mines consistent with {}, {} is {}", around, at(i), verdict));
+    }
+
+    let ((x, y), p) = probabilities(board).safest()?;
+    make(y * w + x, HintKind::Guess(p), format!(
+        "No logical move is available; {} is the lowest-risk guess with a {:.0}% chance of a mine", at(y * w + x), p * 100.0
+    ))
+}
+
+fn plural(n: usize, word: &str) -> String {
+    if n == 1 { word.to_string() } else { format!("{}s", word) }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut c = s.chars();
+    match c.next() { Some(f) => f.to_uppercase().chain(c).collect(), None => String::new() }
+}
+
+fn join(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [one] => one.clone(),
+        [init @ .., last] => format!("{} and {}", init.join(", "), last),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Known {
+    Unknown,
+    Mine,
+    Safe,
+}
+
+/// A revealed number's requirement: exactly `mines` of `cells` are mines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Constraint {
+    origin: usize,
+    cells: Vec<usize>,
+    mines: usize,
+}
+
+/// Mine arrangements of one frontier component, grouped by how many mines they use:
+/// `k -> (number of arrangements, per-cell count of arrangements with a mine there)`.
+struct Component {
+    cells: Vec<usize>,
+    /// Constraints over indices into `cells`.
+    cons: Vec<(Vec<usize>, usize)>,
+    by_mines: BTreeMap<usize, (f64, Vec<f64>)>,
+}
+
+/// Working copy of what the player can see, indexed like `Board::cells`.
+struct View {
+    width: usize,
+    mines: usize,
+    numbers: Vec<Option<u8>>,
+    neighbors: Vec<Vec<usize>>,
+    /// State as shown on the board (revealed = Safe, flagged or revealed mine = Mine).
+    visible: Vec<Known>,
+    /// Visible state plus everything deduced so far.
+    known: Vec<Known>,
+}
+
+impl View {
+    fn new(board: &Board) -> Self {
+        let (w, h) = (board.width(), board.height());
+        let mut numbers = vec![None; w * h];
+        let mut visible = vec![Known::Unknown; w * h];
+        let mut neighbors = vec![Vec::new(); w * h];
+        for y in 0..h {
+            for x in 0..w {
+                let i = y * w + x;
+                let c = board.cell(x, y).expect("in bounds");
+                // Mask holes are not cells: treat them as known safe squares with no number.
+                if !board.playable(x, y) { visible[i] = Known::Safe; continue; }
+                if c.revealed() && c.is_mine() {
+                    visible[i] = Known::Mine;
+                } else if c.revealed() {
+                    visible[i] = Known::Safe;
+                    numbers[i] = Some(c.adjacent());
+                } else if c.flagged() {
+                    visible[i] = Known::Mine;
+                }
+                neighbors[i] = board.neighbors(x, y).map(|(nx, ny)| ny * w + nx).collect();
+            }
+        }
+        let known = visible.clone();
+        Self { width: w, mines: board.mines(), numbers, neighbors, visible, known }
+    }
+
+    fn constraints(&self) -> Vec<Constraint> {
+        let mut out = Vec::new();
+        for (i, n) in self.numbers.iter().enumerate() {
+            let Some(n) = *n else { continue };
+            let mut cells = Vec::new();
+            let mut found = 0usize;
+            for &j in &self.neighbors[i] {
+                match self.known[j] {
+                    Known::Unknown => cells.push(j),
+                    Known::Mine => found += 1,
+                    Known::Safe => {}
+                }
+            }
+            // Too many flags around this number: the player's flags are inconsistent.
+            if cells.is_empty() || found > n as usize { continue; }
+            cells.sort_unstable();
+            out.push(Constraint { origin: i, cells, mines: n as usize - found });
+        }
+        out
+    }
+
+    fn mark(&mut self, cells: &[usize], k: Known) -> bool {
+        let mut changed = false;
+        for &c in cells {
+            if self.known[c] == Known::Unknown { self.known[c] = k; changed = true; }
+        }
+        changed
+    }
+
+    /// Single-cell and subset rules. Returns whether anything new was deduced.
+    fn apply_simple_rules(&mut self) -> bool {
+        let cons = self.constraints();
+        let mut progress = false;
+        for c in &cons {
+            if c.mines == 0 { progress |= self.mark(&c.cells, Known::Safe); }
+            else if c.mines > c.cells.len() { continue; }
+            else if c.mines == c.cells.len() { progress |= self.mark(&c.cells, Known::Mine); }
+        }
+        if progress { return true; }
+
+        let mut by_cell: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (ci, c) in cons.iter().enumerate() {
+            for &cell in &c.cells { by_cell.entry(cell).or_default().push(ci); }
+        }
+        for a in &cons {
+            for &bi in &by_cell[&a.cells[0]] {
+                let b = &cons[bi];
+                if b.cells.len() <= a.cells.len() || b.mines < a.mines { continue; }
+                if !a.cells.iter().all(|c| b.cells.binary_search(c).is_ok()) { continue; }
+                let rest: Vec<usize> = b.cells.iter().copied().filter(|c| a.cells.binary_search(c).is_err()).collect();
+                let dm = b.mines - a.mines;
+                if dm == 0 { progress |= self.mark(&rest, Known::Safe); }
+                else if dm == rest.len() { progress |= self.mark(&rest, Known::Mine); }
+            }
+        }
+        progress
+    }
+
+    /// Remaining mines not yet accounted for by flags or deductions.
+    fn remaining_mines(&self) -> usize {
+        self.mines.saturating_sub(self.known.iter().filter(|&&k| k == Known::Mine).count())
+    }
+
+    /// Split the current constraints into independent components and enumerate
+    /// each one. Components over the search budget come back with `by_mines` empty.
+    fn components(&self) -> Vec<Component> {
+        let cons = self.constraints();
+        let n = self.known.len();
+        let mut parent: Vec<usize> = (0..n).collect();
+        fn find(p: &mut [usize], mut x: usize) -> usize {
+            while p[x] != x { p[x] = p[p[x]]; x = p[x]; }
+            x
+        }
+        for c in &cons {
+            let r0 = find(&mut parent, c.cells[0]);
+            for &cell in &c.cells[1..] {
+                let r = find(&mut parent, cell);
+                parent[r] = r0;
+            }
+        }
+        let mut groups: BTreeMap<usize, (Vec<usize>, Vec<&Constraint>)> = BTreeMap::new();
+        let mut seen = vec![false; n];
+        for c in &cons {
+            let root = find(&mut parent, c.cells[0]);
+            let g = groups.entry(root).or_default();
+            g.1.push(c);
+            for &cell in &c.cells {
+                if !seen[cell] { seen[cell] = true; g.0.push(cell); }
+            }
+        }
+        groups.into_values().map(|(cells, cs)| enumerate(cells, &cs)).collect()
+    }
+
+    /// Deduce from full enumeration plus the global mine count.
+    fn apply_enumeration(&mut self) -> bool {
+        let comps = self.components();
+        let remaining = self.remaining_mines();
+        let in_frontier: usize = comps.iter().map(|c| c.cells.len()).sum();
+        let unknown = self.known.iter().filter(|&&k| k == Known::Unknown).count();
+        let interior = unknown - in_frontier;
+
+        // Achievable mine totals per component (all counts if it was over budget).
+        let options: Vec<Vec<usize>> = comps.iter()
+            .map(|c| if c.by_mines.is_empty() { (0..=c.cells.len()).collect() } else { c.by_mines.keys().copied().collect() })
+            .collect();
+        let feasible_total = |s: usize| s <= remaining && remaining - s <= interior;
+
+        let mut progress = false;
+        for (ci, comp) in comps.iter().enumerate() {
+            if comp.by_mines.is_empty() { continue; }
+            let others = sums(options.iter().enumerate().filter(|&(j, _)| j != ci).map(|(_, o)| o.as_slice()), remaining);
+            let ks: Vec<usize> = comp.by_mines.keys().copied()
+                .filter(|&k| others.iter().any(|&s| feasible_total(k + s)))
+                .collect();
+            // No consistent arrangement: the visible state (flags) is contradictory.
+            if ks.is_empty() { return false; }
+            for (li, &cell) in comp.cells.iter().enumerate() {
+                let always = ks.iter().all(|k| { let (n, per) = &comp.by_mines[k]; per[li] == *n });
+                let never = ks.iter().all(|k| comp.by_mines[k].1[li] == 0.0);
+                if always { progress |= self.mark(&[cell], Known::Mine); }
+                else if never { progress |= self.mark(&[cell], Known::Safe); }
+            }
+        }
+
+        if interior > 0 {
+            let totals: Vec<usize> = sums(options.iter().map(|o| o.as_slice()), remaining).into_iter().filter(|&s| feasible_total(s)).collect();
+            let frontier: BTreeSet<usize> = comps.iter().flat_map(|c| c.cells.iter().copied()).collect();
+            let rest: Vec<usize> = (0..self.known.len()).filter(|&i| self.known[i] == Known::Unknown && !frontier.contains(&i)).collect();
+            if !totals.is_empty() && totals.iter().all(|&s| s == remaining) {
+                progress |= self.mark(&rest, Known::Safe);
+            } else if !totals.is_empty() && totals.iter().all(|&s| remaining - s == interior) {
+                progress |= self.mark(&rest, Known::Mine);
+            }
+        }
+        progress
+    }
+}
+
+/// All totals up to `limit` reachable by picking one value from each option list.
+fn sums<'a>(options: impl Iterator<Item = &'a [usize]>, limit: usize) -> Vec<usize> {
+    let mut reach = vec![false; limit + 1];
+    reach[0] = true;
+    for opts in options {
+        let mut next = vec![false; limit + 1];
+        for (s, _) in reach.iter().enumerate().filter(|(_, r)| **r) {
+            for &k in opts {
+                if s + k <= limit { next[s + k] = true; }
+            }
+        }
+        reach = next;
+    }
+    reach.iter().enumerate().filter(|(_, r)| **r).map(|(s, _)| s).collect()
+}
+
+// Backtracking enumeration of one component's consistent arrangements.
+fn enumerate(cells: Vec<usize>, cons: &[&Constraint]) -> Component {
+    let local: BTreeMap<usize, usize> = cells.iter().enumerate().map(|(li, &c)| (c, li)).collect();
+    let cs: Vec<(Vec<usize>, usize)> = cons.iter().map(|c| (c.cells.iter().map(|x| local[x]).collect(), c.mines)).collect();
+    let mut s = Search::new(cells.len(), &cs, None);
+    let complete = s.go(0, 0);
+    let by_mines = if complete { s.by_mines } else { BTreeMap::new() };
+    Component { cells, cons: cs, by_mines }
+}
+
+// Approximate a component that is too large to enumerate by collecting random
+// consistent arrangements from randomized depth-first searches.
+fn sample(comp: &Component, rng: &mut XorShift64) -> BTreeMap<usize, (f64, Vec<f64>)> {
+    let mut by_mines = BTreeMap::new();
+    for _ in 0..SAMPLES {
+        let mut s = Search::new(comp.cells.len(), &comp.cons, Some(&mut *rng));
+        s.go(0, 0);
+        for (k, (n, per)) in s.by_mines {
+            let e = by_mines.entry(k).or_insert_with(|| (0.0, vec![0.0; per.len()]));
+            e.0 += n;
+            for (a, b) in e.1.iter_mut().zip(per) { *a += b; }
+        }
+    }
+    by_mines
+}
+
+struct Search<'a> {
+    cs: &'a [(Vec<usize>, usize)],
+    of_cell: Vec<Vec<usize>>,
+    placed: Vec<usize>,
+    open: Vec<usize>,
+    assign: Vec<bool>,
+    nodes: usize,
+    // Sampling mode: random value order, stop at the first arrangement found.
+    rng: Option<&'a mut XorShift64>,
+    by_mines: BTreeMap<usize, (f64, Vec<f64>)>,
+}
+
+impl<'a> Search<'a> {
+    fn new(n: usize, cs: &'a [(Vec<usize>, usize)], rng: Option<&'a mut XorShift64>) -> Self {
+        let mut of_cell: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (ci, (cc, _)) in cs.iter().enumerate() {
+            for &li in cc { of_cell[li].push(ci); }
+        }
+        Self {
+            cs,
+            of_cell,
+            placed: vec![0; cs.len()],
+            open: cs.iter().map(|(cc, _)| cc.len()).collect(),
+            assign: vec![false; n],
+            nodes: 0,
+            rng,
+            by_mines: BTreeMap::new(),
+        }
+    }
+
+    // Returns false when the search should stop (budget exhausted or sample found).
+    fn go(&mut self, i: usize, mines: usize) -> bool {
+        self.nodes += 1;
+        let budget = if self.rng.is_some() { SAMPLE_BUDGET } else { NODE_BUDGET };
+        if self.nodes > budget { return false; }
+        if i == self.assign.len() {
+            let e = self.by_mines.entry(mines).or_insert_with(|| (0.0, vec![0.0; self.assign.len()]));
+            e.0 += 1.0;
+            for (li, &m) in self.assign.iter().enumerate() { if m { e.1[li] += 1.0; } }
+            return self.rng.is_none();
+        }
+        let flip = self.rng.as_mut().is_some_and(|r| r.next_u64() & 1 == 1);
+        let order = if flip { [true, false] } else { [false, true] };
+        for v in order {
+            let ok = self.of_cell[i].iter().all(|&ci| {
+                let placed = self.placed[ci] + v as usize;
+                let open = self.open[ci] - 1;
+                placed <= self.cs[ci].1 && placed + open >= self.cs[ci].1
+            });
+            if !ok { continue; }
+            for k in 0..self.of_cell[i].len() {
+                let ci = self.of_cell[i][k];
+                self.placed[ci] += v as usize;
+                self.open[ci] -= 1;
+            }
+            self.assign[i] = v;
+            let more = self.go(i + 1, mines + v as usize);
+            for k in 0..self.of_cell[i].len() {
+                let ci = self.of_cell[i][k];
+                self.placed[ci] -= v as usize;
+                self.open[ci] += 1;
+            }
+            if !more { return false; }
+        }
+        self.assign[i] = false;
+        true
+    }
+}
@@ -0,0 +1,70 @@
+//! Competitive game metrics: 3BV, click counts, 3BV/s, efficiency and IOE.
+//!
+//! 3BV ("Bechtel's Board Benchmark Value") is the least number of left clicks
+//! that clears the board: one per opening plus one per safe cell that no opening
+//! uncovers. The solved part counts the openings and cells already revealed, so
+//! the rates also make sense for lost games.
+
+use std::fmt;
+use std::time::Duration;
+
+/// Clicks made on a board, counted whether or not they changed anything.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Clicks {
+    /// Reveals.
+    pub left: usize,
+    /// Flag (and question mark) toggles.
+    pub right: usize,
+    /// Chords.
+    pub chord: usize,
+    /// Clicks that were rejected or changed nothing.
+    pub wasted: usize,
+}
+
+impl Clicks {
+    pub fn total(&self) -> usize { self.left.saturating_add(self.right).saturating_add(self.chord) }
+    pub fn effective(&self) -> usize { self.total().saturating_sub(self.wasted) }
+}
+
+/// Snapshot of a board's metrics, from `Board::stats`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    /// 3BV of the mine layout; 0 until the first reveal places the mines.
+    pub bbbv: usize,
+    pub solved_bbbv: usize,
+    pub clicks: Clicks,
+    pub elapsed: Duration,
+}
+
+impl Stats {
+    /// Solved 3BV per second of game time.
+    pub fn bbbv_per_sec(&self) -> Option<f64> {
+        let secs = self.elapsed.as_secs_f64();
+        (secs > 0.0).then(|| self.solved_bbbv as f64 / secs)
+    }
+
+    /// Solved 3BV per effective click, in percent; above 100% means chording
+    /// saved clicks.
+    pub fn efficiency(&self) -> Option<f64> {
+        let n = self.clicks.effective();
+        (n > 0).then(|| 100.0 * self.solved_bbbv as f64 / n as f64)
+    }
+
+    /// Index of efficiency: solved 3BV per click, wasted clicks included.
+    pub fn ioe(&self) -> Option<f64> {
+        let n = self.clicks.total();
+        (n > 0).then(|| self.solved_bbbv as f64 / n as f64)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opt = |v: Option<f64>, digits: usize| v.map_or("-".to_string(), |v| format!("{:.*}", digits, v));
+        let c = &self.clicks;
+        write!(
+            f, "3BV {}/{}  3BV/s {}  Clicks {} (L{} R{} C{}, {} wasted)  Eff {}%  IOE {}",
+            self.solved_bbbv, self.bbbv, opt(self.bbbv_per_sec(), 2), c.total(), c.left, c.right, c.chord, c.wasted,
+            opt(self.efficiency(), 0), opt(self.ioe(), 3),
+        )
+    }
+}
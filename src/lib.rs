@@ -1,3 +1,10 @@
+pub mod daily;
+pub mod date;
 pub mod engine;
+pub mod paths;
+pub mod presets;
+pub mod protocol;
+pub mod scores;
+pub mod server;
+pub mod stats;
 pub mod tui;
-
@@ -1,15 +1,39 @@
 use std::io::{self, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use clap::Parser;
-use minesweeper::engine::{Board, RevealResult};
-use minesweeper::tui;
+use clap::{Parser, Subcommand, ValueEnum};
+use minesweeper::engine::replay::{Player, Replay, Step};
+use minesweeper::engine::flags::FlagsGame;
+use minesweeper::engine::solver::HintKind;
+use minesweeper::engine::mask::Mask;
+use minesweeper::engine::rng::SeedSpec;
+use minesweeper::daily::{Attempt, Daily, DailyLog};
+use minesweeper::engine::{save, solver, Action, Board, RevealResult, Topology};
+use minesweeper::presets;
+use minesweeper::protocol::{self, Session};
+use minesweeper::scores::{Category, Score, ScoreTable};
+use minesweeper::server::{self, Server};
+use minesweeper::stats::{self as lifetime, GameRecord, StatsLog};
+use minesweeper::tui::{self, TuiOptions};
 
 #[derive(Parser, Debug)]
 #[command(name = "minesweeper", about = "Rust CLI/TUI Minesweeper", version)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// Launch TUI mode
     #[arg(long)]
     tui: bool,
+    /// Two-player split-screen race in the TUI (WASD vs arrows) on identical boards
+    #[arg(long, conflicts_with_all = ["load", "record", "replay"])]
+    race: bool,
+    /// Two players take turns hunting mines on one board ("Flags")
+    #[arg(long, conflicts_with_all = ["load", "record", "replay", "race", "protocol"])]
+    flags: bool,
     /// Board width
     #[arg(long, default_value_t = 9)]
     width: usize,
@@ -19,83 +43,515 @@ struct Args {
     /// Number of mines
     #[arg(long, default_value_t = 10)]
     mines: usize,
+    /// Difficulty preset: beginner, intermediate, expert, a name from the
+    /// presets file, or WIDTHxHEIGHT/MINES
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["width", "height", "mines"])]
+    preset: Option<String>,
     /// Seed (0 = random)
     #[arg(long, default_value_t = 0)]
     seed: u64,
+    /// Generate boards that can be solved without guessing
+    #[arg(long)]
+    no_guess: bool,
+    /// Play on a hexagonal grid (six neighbors per cell)
+    #[arg(long)]
+    hex: bool,
+    /// Join opposite edges so the board wraps around like a torus
+    #[arg(long)]
+    wrap: bool,
+    /// Flag toggling cycles covered -> flag -> ? -> covered
+    #[arg(long)]
+    question_marks: bool,
+    /// Play the board of a seed string such as xs64:12345:30x16:99 (see README)
+    #[arg(long, value_name = "SEED", conflicts_with_all = ["width", "height", "mines", "preset", "seed", "no_guess", "hex", "wrap", "mask", "load", "record", "race", "flags"])]
+    board: Option<SeedSpec>,
+    /// Play today's challenge: the same board for everyone, one scored attempt per day
+    #[arg(long, conflicts_with_all = ["width", "height", "mines", "preset", "seed", "no_guess", "hex", "wrap", "mask", "board", "load", "record", "race", "flags", "protocol"])]
+    daily: bool,
+    /// Board shape: ASCII art where '#' is a cell and a space is a hole
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["width", "height", "preset"])]
+    mask: Option<PathBuf>,
+    /// Resume a game saved with `S` (TUI) or `save` (CLI)
+    #[arg(long, value_name = "FILE")]
+    load: Option<PathBuf>,
+    /// Record every move of the game into a replay file
+    #[arg(long, value_name = "FILE", conflicts_with = "load")]
+    record: Option<PathBuf>,
+    /// Name the finished games are recorded under in the lifetime statistics
+    /// (default: $USER)
+    #[arg(long, value_name = "NAME", global = true)]
+    player: Option<String>,
+    /// Print the best-times table and exit
+    #[arg(long)]
+    scores: bool,
+    /// Play back a replay file instead of starting a game
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["load", "record"])]
+    replay: Option<PathBuf>,
+    /// Speak a machine-readable protocol on stdin/stdout instead of the text prompt
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with_all = ["tui", "record", "replay", "scores"])]
+    protocol: Option<Protocol>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Host games for JSON protocol clients over TCP; board options set the
+    /// defaults for new sessions
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = server::DEFAULT_ADDR)]
+        addr: String,
+        /// Most clients connected at once; more are turned away
+        #[arg(long, default_value_t = server::MAX_CONNECTIONS)]
+        max_connections: usize,
+        /// Most sessions one connection may hold open at once
+        #[arg(long, default_value_t = server::MAX_SESSIONS)]
+        max_sessions: usize,
+        /// Seconds before a silent connection is closed and an unused session dropped
+        #[arg(long, value_name = "SECS", default_value_t = server::IDLE_TIMEOUT.as_secs())]
+        idle_timeout: u64,
+    },
+    /// Print lifetime statistics for every player, or only `--player`
+    Stats {
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Protocol {
+    /// One JSON object per line (see README)
+    Json,
+}
+
+const DEFAULT_SAVE_FILE: &str = "minesweeper.msw";
+
 fn print_help() {
     println!("Commands:");
     println!("  r x y   - reveal cell at column x, row y (1-based)");
-    println!("  f x y   - toggle flag at x, y (1-based)");
+    println!("  f x y   - toggle flag at x, y (1-based); with --question-marks cycles flag, ?, none");
+    println!("  c x y   - chord: reveal neighbors of a number whose flags are all placed");
+    println!("  undo    - undo the last move (also after hitting a mine)");
+    println!("  redo    - redo the last undone move");
+    println!("  solve   - list cells that are certainly safe or certainly mines");
+    println!("  hint    - suggest one move and explain why");
+    println!("  prob    - show each covered cell's mine probability in percent");
+    println!("  save [file] - save the game (default {})", DEFAULT_SAVE_FILE);
+    println!("  load [file] - load a saved game");
     println!("  q       - quit");
     println!("  h/help  - show this help");
 }
 
+fn status_line(board: &Board) -> String {
+    let face = if !board.alive() { "X(" } else if board.won() { "B)" } else { ":)" };
+    format!("Mines left: {}  Time: {}s  {}", board.mines_left(), board.elapsed().as_secs(), face)
+}
+
+fn record_win(board: &Board) {
+    let Some(score) = Score::from_board(board) else { return };
+    let mut table = ScoreTable::load(&ScoreTable::default_path());
+    if let Some(rank) = table.submit(score) { println!("New best time #{} for this configuration!", rank); }
+    if let Err(e) = table.save() { eprintln!("Cannot save scores: {}", e); }
+    println!("{}", table.render_category(Category::of(board)));
+}
+
+// Append a finished game to the lifetime statistics.
+fn record_game(board: &Board, player: &str) {
+    let Some(game) = GameRecord::from_board(board, player) else { return };
+    if let Err(e) = StatsLog::load(&StatsLog::default_path()).record(game) { eprintln!("Cannot save stats: {}", e); }
+}
+
+fn record_daily(attempt: Attempt) {
+    if let Err(e) = DailyLog::load(&DailyLog::default_path()).record(attempt) { eprintln!("Cannot save the daily result: {}", e); }
+}
+
+fn print_stats(player: Option<&str>, json: bool) {
+    let summaries = StatsLog::load(&StatsLog::default_path()).summaries(player.map(lifetime::player_name).as_deref());
+    if json {
+        match serde_json::to_string_pretty(&summaries) {
+            Ok(text) => println!("{}", text),
+            Err(e) => eprintln!("Cannot encode stats: {}", e),
+        }
+    } else if summaries.is_empty() {
+        println!("No games played yet.");
+    } else {
+        print!("{}", summaries.iter().map(lifetime::render).collect::<Vec<_>>().join("\n"));
+    }
+}
+
+fn print_replay_help() {
+    println!("Replay commands:");
+    println!("  <Enter>/s [n] - step forward n events (default 1)");
+    println!("  p             - play to the end with recorded timing");
+    println!("  speed x       - set playback speed multiplier (e.g. 2, 0.5)");
+    println!("  q             - quit");
+}
+
+fn run_replay(mut player: Player) {
+    let r = player.replay();
+    println!("Replay of {}x{} with {} mines (seed {}), {} events", r.width, r.height, r.mines, r.seed, player.len());
+    print_replay_help();
+    let mut speed = 1.0f64;
+    let mut input = String::new();
+    loop {
+        println!("\n{}", player.board());
+        println!("{}", status_line(player.board()));
+        println!("Event {}/{}  speed {}x", player.position(), player.len(), speed);
+        if player.finished() {
+            println!("End of replay.");
+            if !player.board().alive() || player.board().won() {
+                println!("Final board (mines shown):\n{}", player.board().render(true, true));
+            }
+            break;
+        }
+        print!("replay> ");
+        let _ = io::stdout().flush();
+        input.clear();
+        if io::stdin().read_line(&mut input).map(|n| n == 0).unwrap_or(true) { break; }
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        match parts.first().copied().unwrap_or("s") {
+            "q" | "quit" | "exit" => break,
+            "h" | "help" => print_replay_help(),
+            "s" | "step" => {
+                let n = parts.get(1).and_then(|v| v.parse::<usize>().ok()).unwrap_or(1);
+                for _ in 0..n { if player.step().is_none() { break; } }
+            }
+            "p" | "play" => {
+                while let Some(delay) = player.next_delay() {
+                    thread::sleep(delay.div_f64(speed));
+                    player.step();
+                    println!("\n{}", player.board());
+                }
+            }
+            "speed" => match parts.get(1).and_then(|v| v.parse::<f64>().ok()) {
+                Some(v) if v > 0.0 => speed = v,
+                _ => println!("Usage: speed x (x > 0)"),
+            },
+            other => println!("Unknown command '{}'. Type 'h' for help.", other),
+        }
+    }
+}
+
+fn print_flags_help() {
+    println!("Flags: players take turns revealing cells. Uncovering a mine claims it and");
+    println!("earns another turn; the first to claim more than half the mines wins.");
+    println!("  r x y   - reveal cell at column x, row y (1-based)");
+    println!("  q       - quit");
+    println!("  h/help  - show this help");
+}
+
+fn run_flags(mut game: FlagsGame) {
+    let b = game.board();
+    println!("Minesweeper Flags {}x{} with {} mines: first to {} mines wins", b.width(), b.height(), b.mines(), game.target());
+    print_flags_help();
+    let mut input = String::new();
+    loop {
+        println!("\n{}", game.board());
+        let scores = game.scores();
+        println!("Player 1: {}  Player 2: {}  Unclaimed: {}", scores[0], scores[1], game.board().mines() - game.claims().len());
+        if game.finished() {
+            match game.winner() {
+                Some(p) => println!("Player {} wins!", p + 1),
+                None => println!("Every mine is claimed and the score is tied."),
+            }
+            break;
+        }
+        print!("player {}> ", game.turn() + 1);
+        let _ = io::stdout().flush();
+        input.clear();
+        if io::stdin().read_line(&mut input).map(|n| n == 0).unwrap_or(true) { break; }
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        match parts.first().map(|c| c.to_lowercase()).as_deref() {
+            None => continue,
+            Some("q" | "quit" | "exit") => break,
+            Some("h" | "help") => print_flags_help(),
+            Some("r" | "reveal") => {
+                if parts.len() < 3 { println!("Usage: r x y"); continue; }
+                let x = match parts[1].parse::<usize>() { Ok(v) => v, Err(_) => { println!("Invalid x"); continue; } };
+                let y = match parts[2].parse::<usize>() { Ok(v) => v, Err(_) => { println!("Invalid y"); continue; } };
+                if x == 0 || y == 0 { println!("Use 1-based coordinates"); continue; }
+                let player = game.turn();
+                match game.reveal(x-1, y-1) {
+                    Ok(RevealResult::HitMine) => println!("Player {} claims a mine and goes again!", player + 1),
+                    Ok(_) => {}
+                    Err(e) => println!("Cannot reveal: {}", e),
+                }
+            }
+            Some(other) => println!("Unknown command '{}'. Type 'h' for help.", other),
+        }
+    }
+}
+
 fn main() {
-    let args = Args::parse();
-    if args.tui {
-        if let Err(e) = tui::run_tui(args.width, args.height, args.mines, args.seed) {
+    let mut args = Args::parse();
+    if let Some(name) = args.player.as_deref().map(str::trim) {
+        if name.is_empty() || name.contains(char::is_whitespace) { eprintln!("--player needs a name without spaces"); return; }
+        args.player = Some(name.to_string());
+    }
+    if let Some(name) = &args.preset {
+        let (all, errors) = presets::load(&presets::user_path());
+        for e in errors { eprintln!("{}: {}", presets::user_path().display(), e); }
+        match presets::find(&all, name) {
+            Some(p) => (args.width, args.height, args.mines) = (p.width, p.height, p.mines),
+            None => {
+                eprintln!("Unknown preset '{}'. Available presets:", name);
+                for p in &all { eprintln!("  {}", p); }
+                return;
+            }
+        }
+    }
+    if let Some(Command::Stats { json }) = &args.command {
+        print_stats(args.player.as_deref(), *json);
+        return;
+    }
+    if matches!(args.command, Some(Command::Serve { .. })) && (args.tui || args.race || args.flags || args.protocol.is_some()) {
+        eprintln!("serve cannot be combined with --tui, --race, --flags or --protocol");
+        return;
+    }
+    if args.scores {
+        print!("{}", ScoreTable::load(&ScoreTable::default_path()).render());
+        return;
+    }
+    if let Some(path) = &args.replay {
+        let player = match Replay::read(path).and_then(Player::new) {
+            Ok(p) => p,
+            Err(e) => { eprintln!("Cannot load replay {}: {}", path.display(), e); return; }
+        };
+        if args.tui {
+            if let Err(e) = tui::run_replay(player) { eprintln!("TUI error: {}", e); }
+        } else {
+            run_replay(player);
+        }
+        return;
+    }
+    let daily = if args.daily {
+        if args.command.is_some() { eprintln!("--daily cannot be combined with a subcommand"); return; }
+        let daily = Daily::today();
+        if let Some(attempt) = DailyLog::load(&DailyLog::default_path()).get(&daily.date) {
+            println!("You already played the daily challenge for {}{}.\n", daily.date, if attempt.finished { "" } else { " (given up)" });
+            print!("{}", attempt.share());
+            return;
+        }
+        args.board = Some(daily.spec);
+        Some(daily)
+    } else { None };
+    let loaded = match (&args.load, args.board) {
+        (Some(path), _) => match save::read(path) {
+            Ok(b) => Some(b),
+            Err(e) => { eprintln!("Cannot load {}: {}", path.display(), e); return; }
+        },
+        (None, Some(spec)) => match spec.board() {
+            Ok(b) => {
+                // New games in the TUI keep the seed string's settings.
+                (args.width, args.height, args.mines, args.seed) = (spec.width, spec.height, spec.mines, spec.seed);
+                (args.no_guess, args.hex, args.wrap) = (spec.no_guess, spec.topology == Topology::Hex, spec.wrap);
+                Some(b.with_question_marks(args.question_marks))
+            }
+            Err(e) => { eprintln!("Cannot build board {}: {}", spec, e); return; }
+        },
+        (None, None) => None,
+    };
+    let mask = match &args.mask {
+        Some(path) => match Mask::read(path) {
+            Ok(m) => Some(m),
+            Err(e) => { eprintln!("Cannot load mask {}: {}", path.display(), e); return; }
+        },
+        None => None,
+    };
+    if let Some(m) = &mask { (args.width, args.height) = (m.width(), m.height()); }
+    let topology = if args.hex { Topology::Hex } else { Topology::Square };
+    if let (Some(d), Some(b)) = (&daily, &loaded) {
+        // Taken up front, so quitting early still uses up the day's attempt.
+        record_daily(Attempt::from_board(&d.date, b));
+    }
+    let save_path = args.load.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_SAVE_FILE));
+    if args.tui || args.race {
+        let opts = TuiOptions { width: args.width, height: args.height, mines: args.mines, seed: args.seed, no_guess: args.no_guess, topology, wrap: args.wrap, question_marks: args.question_marks, mask: mask.clone(), save_path, board: loaded, record: args.record, player: args.player.clone().unwrap_or_else(lifetime::default_player), daily: daily.clone() };
+        let res = if args.race { tui::run_race(opts) } else if args.flags { tui::run_flags(opts) } else { tui::run_tui(opts) };
+        if let Err(e) = res {
             eprintln!("TUI error: {}", e);
         }
+        if let Some(attempt) = daily.and_then(|d| DailyLog::load(&DailyLog::default_path()).get(&d.date).cloned()) { print!("\n{}", attempt.share()); }
         return;
     }
-    let mut board = match Board::new(args.width, args.height, args.mines, args.seed) {
-        Ok(b) => b,
-        Err(e) => { eprintln!("{}", e); return; }
+    let mut board = match loaded {
+        Some(b) => b,
+        None => match mask.map_or_else(|| Board::new(args.width, args.height, args.mines, args.seed), |m| Board::from_mask(m, args.mines, args.seed))
+            .and_then(|b| b.with_topology(topology)?.with_wrap(args.wrap)) {
+            Ok(b) => b.with_no_guess(args.no_guess).with_question_marks(args.question_marks),
+            Err(e) => { eprintln!("{}", e); return; }
+        },
     };
+    if args.flags {
+        run_flags(FlagsGame::new(board));
+        return;
+    }
+    if let Some(Command::Serve { addr, max_connections, max_sessions, idle_timeout }) = &args.command {
+        let listener = match TcpListener::bind(addr) {
+            Ok(l) => l,
+            Err(e) => { eprintln!("Cannot listen on {}: {}", addr, e); return; }
+        };
+        eprintln!("Serving minesweeper on {}", addr);
+        let server = Server::new(board).with_max_connections(*max_connections).with_max_sessions(*max_sessions).with_idle_timeout(Duration::from_secs(*idle_timeout));
+        if let Err(e) = Arc::new(server).serve(listener) { eprintln!("Server error: {}", e); }
+        return;
+    }
+    if args.protocol == Some(Protocol::Json) {
+        if let Err(e) = protocol::run(&mut Session::new(board), io::stdin().lock(), io::stdout().lock()) { eprintln!("Protocol error: {}", e); }
+        return;
+    }
 
-    println!("Minesweeper {}x{} with {} mines{}", args.width, args.height, args.mines, if args.seed != 0 { format!(" (seed {})", args.seed) } else { String::new() });
+    let mut notes = String::new();
+    if let Some(m) = board.mask() { notes.push_str(&format!(" (shaped board, {} cells)", m.cells())); }
+    if board.wrap() { notes.push_str(" (wrapping edges)"); }
+    if board.no_guess() { notes.push_str(" (no guessing needed)"); }
+    if args.seed != 0 || args.load.is_some() || args.board.is_some() { notes.push_str(&format!(" (seed {})", board.seed())); }
+    println!("Minesweeper {}x{}{} with {} mines{}", board.width(), board.height(), if board.topology() == Topology::Hex { " hex" } else { "" }, board.mines(), notes);
     println!("Coordinates are 1-based. Type 'h' for help.");
     print_help();
 
+    let start = Instant::now();
+    let mut recording = args.record.as_ref().map(|_| Replay::new(&board));
+    // Daily wins only go into the daily log, not the score table.
+    let mut scored = board.won() || daily.is_some();
+    let player = args.player.clone().unwrap_or_else(lifetime::default_player);
+    // Whether the current game already went into the lifetime statistics.
+    let mut logged = !board.alive() || board.won();
     let mut input = String::new();
     loop {
         println!("\n{}", board);
+        println!("{}", status_line(&board));
+        let game_over = !board.alive() || board.won();
         if !board.alive() {
             println!("Boom! You hit a mine. Game over.\n");
             println!("Final board (mines shown):\n{}", board.render(true, true));
-            break;
-        }
-        if board.won() {
+            println!("{}", board.stats());
+            if let Some(spec) = SeedSpec::of(&board) { println!("Board: {}", spec); }
+        } else if board.won() {
             println!("Congratulations! You cleared the board!\n");
             println!("Final board (mines shown):\n{}", board.render(true, true));
-            break;
+            println!("{}", board.stats());
+            if let Some(spec) = SeedSpec::of(&board) { println!("Board: {}", spec); }
+            if !scored {
+                scored = true;
+                record_win(&board);
+            }
+        }
+        if game_over && !logged {
+            logged = true;
+            record_game(&board, &player);
+        }
+        if game_over {
+            if daily.is_some() || !board.can_undo() { break; }
+            println!("Type 'undo' to take back the last move, anything else to quit.");
         }
 
         print!("> ");
         let _ = io::stdout().flush();
         input.clear();
-        if io::stdin().read_line(&mut input).is_err() { break; }
+        if io::stdin().read_line(&mut input).map(|n| n == 0).unwrap_or(true) { break; }
         let line = input.trim();
         if line.is_empty() { continue; }
 
         let parts: Vec<&str> = line.split_whitespace().collect();
-        match parts[0].to_lowercase().as_str() {
+        let cmd = parts[0].to_lowercase();
+        if game_over && cmd != "u" && cmd != "undo" { break; }
+        match cmd.as_str() {
             "q" | "quit" | "exit" => break,
             "h" | "help" => { print_help(); continue; },
+            "u" | "undo" | "redo" | "load" if daily.is_some() => { println!("The daily challenge has one attempt: no undo, redo or loading."); continue; }
+            "solve" | "hint" | "?" | "prob" | "probs" | "save" if daily.is_some() => { println!("The daily challenge is played without help: no solver, hints or saves."); continue; }
+            "u" | "undo" => {
+                if board.undo().is_none() { println!("Nothing to undo"); continue; }
+                if let Some(rec) = recording.as_mut() { rec.push(start.elapsed(), Step::Undo); }
+            }
+            "redo" => {
+                if board.redo().is_none() { println!("Nothing to redo"); continue; }
+                if let Some(rec) = recording.as_mut() { rec.push(start.elapsed(), Step::Redo); }
+            }
+            "solve" => {
+                let d = solver::solve(&board);
+                if d.is_empty() { println!("No certain deductions; the next move is a guess."); continue; }
+                let fmt = |cells: &std::collections::BTreeSet<(usize, usize)>| cells.iter().map(|(x, y)| format!("({},{})", x + 1, y + 1)).collect::<Vec<_>>().join(" ");
+                if !d.safe.is_empty() { println!("Safe:  {}", fmt(&d.safe)); }
+                if !d.mines.is_empty() { println!("Mines: {}", fmt(&d.mines)); }
+                continue;
+            }
+            "hint" | "?" => {
+                match solver::hint(&board) {
+                    Some(h) => {
+                        let verb = match h.kind { HintKind::Safe => "reveal", HintKind::Mine => "flag", HintKind::Guess(_) => "guess" };
+                        println!("Hint: {} ({},{}). {}.", verb, h.x + 1, h.y + 1, h.reason);
+                    }
+                    None => println!("No hint available."),
+                }
+                continue;
+            }
+            "prob" | "probs" => {
+                let p = solver::probabilities(&board);
+                print!("{}", p.render(&board, true));
+                if !p.exact() { println!("(approximate: part of the frontier was sampled)"); }
+                continue;
+            }
+            "save" => {
+                let path = parts.get(1).map(Path::new).unwrap_or(&save_path);
+                match save::write(&board, path) {
+                    Ok(()) => println!("Saved to {}", path.display()),
+                    Err(e) => println!("Save failed: {}", e),
+                }
+            }
+            "load" => {
+                let path = parts.get(1).map(Path::new).unwrap_or(&save_path);
+                match save::read(path) {
+                    Ok(b) => {
+                        board = b;
+                        scored = board.won();
+                        logged = !board.alive() || board.won();
+                        println!("Loaded {}", path.display());
+                        if recording.take().is_some() { println!("Recording stopped: a loaded game cannot be replayed"); }
+                    }
+                    Err(e) => println!("Load failed: {}", e),
+                }
+            }
             "r" | "reveal" => {
                 if parts.len() < 3 { println!("Usage: r x y"); continue; }
                 let x = match parts[1].parse::<usize>() { Ok(v) => v, Err(_) => { println!("Invalid x"); continue; } };
                 let y = match parts[2].parse::<usize>() { Ok(v) => v, Err(_) => { println!("Invalid y"); continue; } };
                 if x == 0 || y == 0 { println!("Use 1-based coordinates"); continue; }
-                let res = board.reveal(x-1, y-1);
-                match res {
-                    RevealResult::HitMine => { /* handled at loop top */ },
-                    RevealResult::RevealedSafe => { /* ok */ },
-                    RevealResult::NoOp => { /* ignore */ },
-                }
+                // A mine hit is reported at the top of the loop.
+                if let Err(e) = board.try_reveal(x-1, y-1) { println!("Cannot reveal: {}", e); continue; }
+                if let Some(rec) = recording.as_mut() { rec.push(start.elapsed(), Step::Move(Action::Reveal(x-1, y-1))); }
             }
             "f" | "flag" => {
                 if parts.len() < 3 { println!("Usage: f x y"); continue; }
                 let x = match parts[1].parse::<usize>() { Ok(v) => v, Err(_) => { println!("Invalid x"); continue; } };
                 let y = match parts[2].parse::<usize>() { Ok(v) => v, Err(_) => { println!("Invalid y"); continue; } };
                 if x == 0 || y == 0 { println!("Use 1-based coordinates"); continue; }
-                if !board.toggle_flag(x-1, y-1) { println!("Cannot flag revealed cell or out of bounds"); }
+                if let Err(e) = board.try_toggle_flag(x-1, y-1) { println!("Cannot flag: {}", e); continue; }
+                if let Some(rec) = recording.as_mut() { rec.push(start.elapsed(), Step::Move(Action::ToggleFlag(x-1, y-1))); }
+            }
+            "c" | "chord" => {
+                if parts.len() < 3 { println!("Usage: c x y"); continue; }
+                let x = match parts[1].parse::<usize>() { Ok(v) => v, Err(_) => { println!("Invalid x"); continue; } };
+                let y = match parts[2].parse::<usize>() { Ok(v) => v, Err(_) => { println!("Invalid y"); continue; } };
+                if x == 0 || y == 0 { println!("Use 1-based coordinates"); continue; }
+                if let Err(e) = board.try_chord(x-1, y-1) { println!("Cannot chord: {}", e); continue; }
+                if let Some(rec) = recording.as_mut() { rec.push(start.elapsed(), Step::Move(Action::Chord(x-1, y-1))); }
             }
             other => {
                 println!("Unknown command '{}'. Type 'h' for help.", other);
             }
         }
     }
+    if let Some(d) = &daily {
+        let attempt = Attempt::from_board(&d.date, &board);
+        record_daily(attempt.clone());
+        print!("\n{}", attempt.share());
+    }
+    if let (Some(rec), Some(path)) = (recording, &args.record) {
+        match rec.write(path) {
+            Ok(()) => println!("Replay written to {}", path.display()),
+            Err(e) => eprintln!("Cannot write replay {}: {}", path.display(), e),
+        }
+    }
 }
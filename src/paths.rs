@@ -0,0 +1,24 @@
+//! Locations of local data and configuration files.
+
+use std::env;
+use std::path::PathBuf;
+
+/// Directory for persistent game data: `$MINESWEEPER_DATA_DIR`, else
+/// `$XDG_DATA_HOME/minesweeper`, else `~/.local/share/minesweeper`, else the
+/// current directory.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("MINESWEEPER_DATA_DIR") { return PathBuf::from(dir); }
+    if let Some(dir) = env::var_os("XDG_DATA_HOME") { return PathBuf::from(dir).join("minesweeper"); }
+    if let Some(home) = env::var_os("HOME") { return PathBuf::from(home).join(".local/share/minesweeper"); }
+    PathBuf::from(".")
+}
+
+/// Directory for user configuration: `$MINESWEEPER_CONFIG_DIR`, else
+/// `$XDG_CONFIG_HOME/minesweeper`, else `~/.config/minesweeper`, else the
+/// current directory.
+pub fn config_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("MINESWEEPER_CONFIG_DIR") { return PathBuf::from(dir); }
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME") { return PathBuf::from(dir).join("minesweeper"); }
+    if let Some(home) = env::var_os("HOME") { return PathBuf::from(home).join(".config/minesweeper"); }
+    PathBuf::from(".")
+}
@@ -0,0 +1,95 @@
+//! Difficulty presets: the three classic levels plus user-defined ones.
+//!
+//! User presets live in `presets.txt` in the config directory, one per line:
+//!
+//! ```text
+//! # name = WIDTHxHEIGHT/MINES
+//! tiny = 5x5/3
+//! wide = 40x12/80
+//! ```
+//!
+//! A user preset with the same name as a built-in one replaces it.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preset {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+}
+
+impl Preset {
+    fn new(name: &str, width: usize, height: usize, mines: usize) -> Self {
+        Self { name: name.to_string(), width, height, mines }
+    }
+
+    /// Parse a `WIDTHxHEIGHT/MINES` spec such as `30x16/99`.
+    pub fn parse_spec(name: &str, spec: &str) -> Option<Self> {
+        let (size, mines) = spec.trim().split_once('/')?;
+        let (w, h) = size.trim().split_once(['x', 'X'])?;
+        Some(Self::new(name, w.trim().parse().ok()?, h.trim().parse().ok()?, mines.trim().parse().ok()?))
+    }
+
+    pub fn spec(&self) -> String { format!("{}x{}/{}", self.width, self.height, self.mines) }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.spec())
+    }
+}
+
+/// Beginner, Intermediate and Expert.
+pub fn builtin() -> Vec<Preset> {
+    vec![
+        Preset::new("beginner", 9, 9, 10),
+        Preset::new("intermediate", 16, 16, 40),
+        Preset::new("expert", 30, 16, 99),
+    ]
+}
+
+pub fn user_path() -> PathBuf { paths::config_dir().join("presets.txt") }
+
+/// Parse a user preset file. Malformed lines are reported, not fatal.
+pub fn parse_user(text: &str) -> (Vec<Preset>, Vec<String>) {
+    let mut presets = Vec::new();
+    let mut errors = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let parsed = line.split_once('=').and_then(|(name, spec)| {
+            let name = name.trim();
+            if name.is_empty() { None } else { Preset::parse_spec(&name.to_lowercase(), spec) }
+        });
+        match parsed {
+            Some(p) => presets.push(p),
+            None => errors.push(format!("line {}: expected 'name = WIDTHxHEIGHT/MINES', got '{}'", n + 1, line)),
+        }
+    }
+    (presets, errors)
+}
+
+/// Built-in presets merged with the user's file (missing file = none).
+pub fn load(path: &Path) -> (Vec<Preset>, Vec<String>) {
+    let mut all = builtin();
+    let (user, errors) = fs::read_to_string(path).map(|t| parse_user(&t)).unwrap_or_default();
+    for p in user {
+        match all.iter_mut().find(|b| b.name == p.name) {
+            Some(existing) => *existing = p,
+            None => all.push(p),
+        }
+    }
+    (all, errors)
+}
+
+/// Resolve `--preset`: a preset name (case-insensitive) or a literal `WxH/M` spec.
+pub fn find(presets: &[Preset], name: &str) -> Option<Preset> {
+    let lower = name.to_lowercase();
+    presets.iter().find(|p| p.name == lower).cloned().or_else(|| Preset::parse_spec("custom", name))
+}
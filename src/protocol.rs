@@ -0,0 +1,316 @@
+//! JSON line protocol for bots.
+//!
+//! Every input line is one request object and every request gets exactly one
+//! response object on its own output line. Coordinates are 0-based. The README
+//! documents every message; this module is the reference implementation.
+//!
+//! ```text
+//! > {"op":"reveal","x":3,"y":4}
+//! < {"ok":true,"op":"reveal","result":"revealed","revealed":[{"x":3,"y":4,"n":1}],"covered":[],"status":{...}}
+//! ```
+
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Board, Error, RevealResult, Topology};
+
+/// Protocol version announced in the `hello` message; bumped on breaking changes.
+pub const VERSION: u32 = 1;
+/// Widest board a `new` request may ask for.
+pub const MAX_WIDTH: usize = 1000;
+/// Tallest board a `new` request may ask for.
+pub const MAX_HEIGHT: usize = 1000;
+/// Most cells a `new` request may ask for, whatever the shape.
+pub const MAX_CELLS: usize = 250_000;
+
+/// One decoded input line.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    New(NewGame),
+    Reveal { x: usize, y: usize },
+    Flag { x: usize, y: usize },
+    Chord { x: usize, y: usize },
+    Undo,
+    Redo,
+    State,
+    Quit,
+}
+
+/// Parameters of a `new` request; anything omitted is taken from the current game,
+/// except the seed, which defaults to a random one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct NewGame {
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub mines: Option<usize>,
+    pub seed: Option<u64>,
+    pub no_guess: Option<bool>,
+    pub topology: Option<String>,
+    pub wrap: Option<bool>,
+}
+
+/// A cell that became revealed: its number, or `mine` for the losing cell.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Revealed {
+    pub x: usize,
+    pub y: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u8>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub mine: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Pos {
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Status {
+    /// `playing`, `won` or `lost`.
+    pub state: &'static str,
+    pub mines_left: isize,
+    pub remaining_safe: usize,
+    pub elapsed_ms: u64,
+}
+
+/// Board parameters, sent with `hello`, `new` and `state`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Game {
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub seed: u64,
+    pub no_guess: bool,
+    pub topology: &'static str,
+    pub wrap: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Response {
+    pub ok: bool,
+    pub op: &'static str,
+    /// Session id, only used by the TCP server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub revealed: Vec<Revealed>,
+    pub covered: Vec<Pos>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game: Option<Game>,
+    /// One string per row: `.` covered, `F` flag, `?` question mark, `0`-`8` number,
+    /// `*` revealed mine, `-` hole.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub board: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+}
+
+impl Response {
+    fn new(op: &'static str, board: &Board) -> Self {
+        Self { status: Some(status(board)), ..Self::bare(op) }
+    }
+
+    /// A response that is not about any board.
+    pub fn bare(op: &'static str) -> Self {
+        Self { ok: true, op, session: None, version: None, result: None, error: None, message: None, revealed: Vec::new(), covered: Vec::new(), game: None, board: None, status: None }
+    }
+
+    pub fn failed(mut self, code: &'static str, message: impl Into<String>) -> Self {
+        self.ok = false;
+        self.error = Some(code);
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("responses always serialize")
+    }
+}
+
+/// One bot's game: decodes requests, plays them on the board and builds responses.
+pub struct Session {
+    board: Board,
+    finished: bool,
+}
+
+impl Session {
+    pub fn new(board: Board) -> Self { Self { board, finished: false } }
+    pub fn board(&self) -> &Board { &self.board }
+    /// True once the client sent `quit`.
+    pub fn finished(&self) -> bool { self.finished }
+
+    /// Greeting sent before the first request.
+    pub fn hello(&self) -> Response {
+        Response { version: Some(VERSION), game: Some(game(&self.board)), ..Response::new("hello", &self.board) }
+    }
+
+    /// Handle one input line.
+    pub fn handle(&mut self, line: &str) -> Response {
+        match serde_json::from_str::<Request>(line) {
+            Ok(req) => self.apply(req),
+            Err(e) => Response::new("invalid", &self.board).failed("bad_request", e.to_string()),
+        }
+    }
+
+    pub fn apply(&mut self, req: Request) -> Response {
+        let before = revealed(&self.board);
+        let (target, chord) = match req {
+            Request::Reveal { x, y } => (Some((x, y)), false),
+            Request::Chord { x, y } => (Some((x, y)), true),
+            _ => (None, false),
+        };
+        let mut resp = match req {
+            Request::New(params) => return self.start(params),
+            Request::Reveal { x, y } => self.play("reveal", |b| b.try_reveal(x, y).map(result_name)),
+            Request::Flag { x, y } => self.play("flag", |b| b.try_toggle_flag(x, y).map(|on| {
+                if on { "flagged" } else if b.cell(x, y).is_some_and(|c| c.questioned()) { "questioned" } else { "unflagged" }
+            })),
+            Request::Chord { x, y } => self.play("chord", |b| b.try_chord(x, y).map(result_name)),
+            Request::Undo => match self.board.undo() {
+                Some(_) => Response { result: Some("undone"), ..Response::new("undo", &self.board) },
+                None => Response::new("undo", &self.board).failed("nothing_to_undo", "nothing to undo"),
+            },
+            Request::Redo => match self.board.redo() {
+                Some(_) => Response { result: Some("redone"), ..Response::new("redo", &self.board) },
+                None => Response::new("redo", &self.board).failed("nothing_to_redo", "nothing to redo"),
+            },
+            Request::State => Response { game: Some(game(&self.board)), board: Some(rows(&self.board)), ..Response::new("state", &self.board) },
+            Request::Quit => {
+                self.finished = true;
+                Response { result: Some("bye"), ..Response::new("quit", &self.board) }
+            }
+        };
+        if resp.result == Some("hit_mine") { resp.revealed.extend(hit_mines(&self.board, target, chord)); }
+        let after = revealed(&self.board);
+        for (i, (&was, &is)) in before.iter().zip(&after).enumerate() {
+            let (x, y) = (i % self.board.width(), i / self.board.width());
+            if is && !was {
+                let Some(c) = self.board.cell(x, y) else { continue };
+                resp.revealed.push(Revealed { x, y, n: (!c.is_mine()).then_some(c.adjacent()), mine: c.is_mine() });
+            } else if was && !is {
+                resp.covered.push(Pos { x, y });
+            }
+        }
+        resp
+    }
+
+    fn play(&mut self, op: &'static str, mv: impl FnOnce(&mut Board) -> Result<&'static str, Error>) -> Response {
+        match mv(&mut self.board) {
+            Ok(result) => Response { result: Some(result), ..Response::new(op, &self.board) },
+            Err(e) => Response::new(op, &self.board).failed(error_code(&e), e.to_string()),
+        }
+    }
+
+    fn start(&mut self, p: NewGame) -> Response {
+        let b = &self.board;
+        let topology = match p.topology.as_deref().map(str::parse::<Topology>) {
+            None => b.topology(),
+            Some(Ok(t)) => t,
+            Some(Err(e)) => return Response::new("new", b).failed("bad_request", e),
+        };
+        let (mines, no_guess, wrap) = (p.mines.unwrap_or(b.mines()), p.no_guess.unwrap_or(b.no_guess()), p.wrap.unwrap_or(b.wrap()));
+        let question_marks = b.question_marks();
+        let (width, height) = (p.width.unwrap_or(b.width()), p.height.unwrap_or(b.height()));
+        if (p.width.is_some() || p.height.is_some()) && (width > MAX_WIDTH || height > MAX_HEIGHT || width.saturating_mul(height) > MAX_CELLS) {
+            let limit = format!("boards are limited to {}x{} and {} cells (asked for {}x{})", MAX_WIDTH, MAX_HEIGHT, MAX_CELLS, width, height);
+            return Response::new("new", b).failed("too_large", limit);
+        }
+        // A shaped board keeps its shape unless the request asks for another size.
+        let board = match b.mask() {
+            Some(mask) if p.width.is_none() && p.height.is_none() => Board::from_mask(mask.clone(), mines, p.seed.unwrap_or(0)),
+            _ => Board::new(width, height, mines, p.seed.unwrap_or(0)),
+        };
+        match board.and_then(|b| b.with_topology(topology)?.with_wrap(wrap)) {
+            Ok(board) => {
+                self.board = board.with_no_guess(no_guess).with_question_marks(question_marks);
+                Response { result: Some("started"), game: Some(game(&self.board)), ..Response::new("new", &self.board) }
+            }
+            Err(e) => Response::new("new", &self.board).failed(error_code(&e), e.to_string()),
+        }
+    }
+}
+
+/// Serve one session: greet, then answer each non-empty input line until `quit`
+/// or end of input.
+pub fn run(session: &mut Session, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    writeln!(output, "{}", session.hello().to_json())?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() { continue; }
+        writeln!(output, "{}", session.handle(&line).to_json())?;
+        output.flush()?;
+        if session.finished() { break; }
+    }
+    Ok(())
+}
+
+/// Stable machine-readable name of an engine error.
+pub fn error_code(e: &Error) -> &'static str {
+    match e {
+        Error::InvalidDimensions { .. } | Error::HexWrapOddHeight { .. } => "invalid_dimensions",
+        Error::TooManyMines { .. } => "too_many_mines",
+        Error::OutOfBounds { .. } => "out_of_bounds",
+        Error::Hole { .. } => "hole",
+        Error::GameOver => "game_over",
+        Error::AlreadyRevealed { .. } => "already_revealed",
+        Error::Flagged { .. } => "flagged",
+        Error::NotANumber { .. } => "not_a_number",
+        Error::FlagMismatch { .. } => "flag_mismatch",
+        Error::NothingToChord { .. } => "nothing_to_chord",
+        Error::Save(_) | Error::Replay(_) | Error::Mask(_) | Error::Io(_) => "io",
+        Error::Seed(_) => "bad_request",
+    }
+}
+
+fn result_name(r: RevealResult) -> &'static str {
+    match r { RevealResult::RevealedSafe => "revealed", RevealResult::HitMine => "hit_mine", RevealResult::NoOp => "no_op" }
+}
+
+fn status(board: &Board) -> Status {
+    let state = if !board.alive() { "lost" } else if board.won() { "won" } else { "playing" };
+    Status { state, mines_left: board.mines_left(), remaining_safe: board.remaining_safe(), elapsed_ms: board.elapsed().as_millis() as u64 }
+}
+
+fn game(board: &Board) -> Game {
+    Game { width: board.width(), height: board.height(), mines: board.mines(), seed: board.seed(), no_guess: board.no_guess(), topology: board.topology().name(), wrap: board.wrap() }
+}
+
+fn revealed(board: &Board) -> Vec<bool> {
+    (0..board.height()).flat_map(|y| (0..board.width()).map(move |x| (x, y)))
+        .map(|(x, y)| board.cell(x, y).is_some_and(|c| c.revealed()))
+        .collect()
+}
+
+// The engine leaves the mine that ended the game covered; report it as revealed.
+fn hit_mines(board: &Board, target: Option<(usize, usize)>, chord: bool) -> Vec<Revealed> {
+    let Some((x, y)) = target else { return Vec::new() };
+    let around: Vec<(usize, usize)> = if chord { board.neighbors(x, y).collect() } else { vec![(x, y)] };
+    around.into_iter()
+        .filter(|&(nx, ny)| board.cell(nx, ny).is_some_and(|c| c.is_mine() && !c.flagged() && !c.revealed()))
+        .map(|(x, y)| Revealed { x, y, n: None, mine: true })
+        .collect()
+}
+
+fn rows(board: &Board) -> Vec<String> {
+    (0..board.height()).map(|y| (0..board.width()).map(|x| match board.cell(x, y) {
+        _ if !board.playable(x, y) => '-',
+        Some(c) if c.revealed() && c.is_mine() => '*',
+        Some(c) if c.revealed() => char::from(b'0' + c.adjacent()),
+        Some(c) if c.flagged() => 'F',
+        Some(c) if c.questioned() => '?',
+        _ => '.',
+    }).collect()).collect()
+}
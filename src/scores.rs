@@ -0,0 +1,217 @@
+//! Persistent best-time table, kept per board configuration.
+//!
+//! The file is plain text, one record per line after a `minesweeper-scores 1`
+//! header:
+//!
+//! ```text
+//! <width> <height> <mines> <no_guess 0|1> <custom 0|1> <time_ms> <board> <unix_date> [tags]
+//! ```
+//!
+//! `<board>` is the winning board's seed string, or `-` for shaped boards. Older files
+//! hold a bare seed there, which reads as an `xs64` seed string without a first click.
+//!
+//! Optional tags describe non-classic boards: a topology name (e.g. `hex`), `wrap`,
+//! and `mask=<fingerprint>` for shaped boards.
+//!
+//! Lines that fail to parse are skipped, so a damaged file loses only the damaged
+//! records; a missing file is an empty table.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::date;
+use crate::engine::rng::{Algorithm, SeedSpec};
+use crate::engine::{Board, Topology};
+use crate::paths;
+use crate::presets;
+
+const HEADER: &str = "minesweeper-scores 1";
+/// Best times kept per configuration.
+pub const TOP_N: usize = 10;
+
+/// A board configuration that scores are ranked within.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Category {
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub no_guess: bool,
+    pub topology: Topology,
+    pub wrap: bool,
+    /// Fingerprint of the board's mask, if it is not a rectangle.
+    pub mask: Option<u64>,
+}
+
+impl Category {
+    pub fn of(board: &Board) -> Self {
+        Self { width: board.width(), height: board.height(), mines: board.mines(), no_guess: board.no_guess(), topology: board.topology(), wrap: board.wrap(), mask: board.mask().map(|m| m.fingerprint()) }
+    }
+
+    /// True unless this is a classic square board matching one of the built-in presets.
+    pub fn custom(&self) -> bool {
+        self.topology != Topology::Square || self.wrap || self.mask.is_some() || !presets::builtin().iter().any(|p| (p.width, p.height, p.mines) == (self.width, self.height, self.mines))
+    }
+
+    pub fn label(&self) -> String {
+        format!(
+            "{}x{}/{}{}{}{}{}{}",
+            self.width, self.height, self.mines,
+            if self.topology == Topology::Square { String::new() } else { format!(" {}", self.topology) },
+            if self.wrap { " wrap" } else { "" },
+            match self.mask { Some(m) => format!(" mask {:04x}", m >> 48), None => String::new() },
+            if self.no_guess { " no-guess" } else { "" },
+            if self.custom() { " (custom)" } else { "" },
+        )
+    }
+
+    // Parse `<width> <height> <mines> <no_guess 0|1>` plus the optional tags.
+    pub(crate) fn parse(fields: &[&str], tags: &[&str]) -> Option<Self> {
+        let (mut topology, mut wrap, mut mask) = (Topology::Square, false, None);
+        for tag in tags {
+            if *tag == "wrap" { wrap = true; }
+            else if let Some(m) = tag.strip_prefix("mask=") { mask = Some(u64::from_str_radix(m, 16).ok()?); }
+            else { topology = tag.parse().ok()?; }
+        }
+        let [w, h, m, ng] = fields else { return None };
+        let no_guess = match *ng { "0" => false, "1" => true, _ => return None };
+        Some(Self { width: w.parse().ok()?, height: h.parse().ok()?, mines: m.parse().ok()?, no_guess, topology, wrap, mask })
+    }
+
+    // Append the tags for a non-classic board, each preceded by a space.
+    pub(crate) fn write_tags(&self, line: &mut String) {
+        if self.topology != Topology::Square { let _ = write!(line, " {}", self.topology); }
+        if self.wrap { line.push_str(" wrap"); }
+        if let Some(m) = self.mask { let _ = write!(line, " mask={:016x}", m); }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Score {
+    pub category: Category,
+    pub time: Duration,
+    /// Seed string of the winning board, so the record can be replayed; `None` for
+    /// shaped boards.
+    pub board: Option<SeedSpec>,
+    /// Unix timestamp of the win.
+    pub date: u64,
+}
+
+impl Score {
+    /// A score for a won board, timed by the engine; `None` for unfinished games,
+    /// games resumed from a save and wins that needed an undo.
+    pub fn from_board(board: &Board) -> Option<Self> {
+        if !board.won() || board.elapsed().is_zero() || board.timer_restored() || board.undo_used() { return None; }
+        Some(Self { category: Category::of(board), time: board.elapsed(), board: SeedSpec::of(board), date: date::now_unix() })
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let f: Vec<&str> = line.split_whitespace().collect();
+        if f.len() < 8 { return None; }
+        let [_custom, ms, board, when] = f[4..8] else { return None };
+        let category = Category::parse(&f[..4], &f[8..])?;
+        Some(Self {
+            category,
+            time: Duration::from_millis(ms.parse().ok()?),
+            board: Self::parse_board(board, &category)?,
+            date: when.parse().ok()?,
+        })
+    }
+
+    // A seed string, `-`, or the bare seed written by older versions.
+    fn parse_board(field: &str, c: &Category) -> Option<Option<SeedSpec>> {
+        if field == "-" { return Some(None); }
+        let Ok(seed) = field.parse::<u64>() else { return field.parse().ok().map(Some) };
+        if seed == 0 || c.mask.is_some() { return Some(None); }
+        Some(Some(SeedSpec {
+            algorithm: Algorithm::XorShift64, seed, width: c.width, height: c.height, mines: c.mines,
+            no_guess: c.no_guess, topology: c.topology, wrap: c.wrap, first_click: None,
+        }))
+    }
+
+    fn line(&self) -> String {
+        let c = &self.category;
+        let board = self.board.map_or_else(|| "-".to_string(), |b| b.to_string());
+        let mut line = format!(
+            "{} {} {} {} {} {} {} {}",
+            c.width, c.height, c.mines, c.no_guess as u8, c.custom() as u8, self.time.as_millis(), board, self.date
+        );
+        c.write_tags(&mut line);
+        line
+    }
+}
+
+pub struct ScoreTable {
+    path: PathBuf,
+    entries: Vec<Score>,
+}
+
+impl ScoreTable {
+    pub fn default_path() -> PathBuf { paths::data_dir().join("scores.txt") }
+
+    /// Load the table; a missing or unreadable file yields an empty table.
+    pub fn load(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .map(|text| text.lines().filter(|l| *l != HEADER).filter_map(Score::parse).collect())
+            .unwrap_or_default();
+        let mut table = Self { path: path.to_path_buf(), entries };
+        table.normalize();
+        table
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() { fs::create_dir_all(dir)?; }
+        let mut s = String::from(HEADER);
+        s.push('\n');
+        for e in &self.entries { s.push_str(&e.line()); s.push('\n'); }
+        fs::write(&self.path, s)
+    }
+
+    /// Add a score; returns its 1-based rank if it made the top list for its category.
+    pub fn submit(&mut self, score: Score) -> Option<usize> {
+        let category = score.category;
+        let time = score.time;
+        self.entries.push(score);
+        self.normalize();
+        self.best(category).iter().position(|s| s.time == time).map(|i| i + 1)
+    }
+
+    /// Best times for a category, fastest first.
+    pub fn best(&self, category: Category) -> Vec<&Score> {
+        self.entries.iter().filter(|s| s.category == category).collect()
+    }
+
+    pub fn categories(&self) -> Vec<Category> {
+        let mut cats: Vec<Category> = self.entries.iter().map(|s| s.category).collect();
+        cats.dedup();
+        cats
+    }
+
+    /// Leaderboard for one category as text lines.
+    pub fn render_category(&self, category: Category) -> String {
+        let mut s = format!("{}\n", category.label());
+        for (i, e) in self.best(category).iter().enumerate() {
+            let _ = write!(s, "{:>3}. {:>8.3}s  {}", i + 1, e.time.as_secs_f64(), date::format_ymd(e.date));
+            if let Some(b) = e.board { let _ = write!(s, "  {}", b); }
+            s.push('\n');
+        }
+        s
+    }
+
+    pub fn render(&self) -> String {
+        if self.entries.is_empty() { return "No scores yet.\n".into(); }
+        self.categories().into_iter().map(|c| self.render_category(c)).collect::<Vec<_>>().join("\n")
+    }
+
+    // Sort by category then time, keeping only the top N of each category.
+    fn normalize(&mut self) {
+        self.entries.sort_by(|a, b| a.category.cmp(&b.category).then(a.time.cmp(&b.time)));
+        let mut kept: Vec<Score> = Vec::with_capacity(self.entries.len());
+        for e in self.entries.drain(..) {
+            if kept.iter().filter(|k| k.category == e.category).count() < TOP_N { kept.push(e); }
+        }
+        self.entries = kept;
+    }
+}
@@ -0,0 +1,192 @@
+//! TCP game server speaking the JSON line protocol of `protocol`.
+//!
+//! Each connection may open any number of sessions, each with its own `Board`.
+//! Sessions are keyed by id and live until closed or left idle for too long, so a
+//! client can reconnect or share a session between connections. The number of
+//! simultaneous connections and of sessions each one holds open are capped, and a
+//! connection that stays silent for the idle timeout is closed. Requests name their session with a `session` field; the
+//! server-level ops are:
+//!
+//! ```text
+//! {"op":"new", ...}             open a session (no "session" field) or restart one
+//! {"op":"close","session":3}    drop a session
+//! {"op":"quit"}                 end this connection
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::engine::Board;
+use crate::protocol::{Request, Response, Session, VERSION};
+
+/// Address `serve` listens on unless told otherwise.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+/// Simultaneous connections allowed unless configured otherwise.
+pub const MAX_CONNECTIONS: usize = 64;
+/// Sessions one connection may hold open at once unless configured otherwise.
+pub const MAX_SESSIONS: usize = 16;
+/// How long a connection may stay silent, and a session unused, by default.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+// The routing fields of a request; everything else is left to `Session`.
+#[derive(Deserialize)]
+struct Envelope {
+    op: String,
+    session: Option<u64>,
+}
+
+// A session and when a request last used it.
+struct Slot {
+    session: Arc<Mutex<Session>>,
+    used: Instant,
+}
+
+/// Shared state of a running server: the session table and the board new
+/// sessions are modelled on.
+pub struct Server {
+    template: Board,
+    sessions: Mutex<HashMap<u64, Slot>>,
+    next_id: AtomicU64,
+    connections: AtomicUsize,
+    max_connections: usize,
+    max_sessions: usize,
+    idle_timeout: Duration,
+}
+
+impl Server {
+    /// `template` supplies the defaults (size, mines, topology, ...) for sessions
+    /// opened without parameters; every session gets its own seed.
+    pub fn new(template: Board) -> Self {
+        Self {
+            template, sessions: Mutex::new(HashMap::new()), next_id: AtomicU64::new(1),
+            connections: AtomicUsize::new(0), max_connections: MAX_CONNECTIONS, max_sessions: MAX_SESSIONS, idle_timeout: IDLE_TIMEOUT,
+        }
+    }
+
+    /// Turn away clients beyond `n` simultaneous connections.
+    pub fn with_max_connections(mut self, n: usize) -> Self {
+        self.max_connections = n;
+        self
+    }
+
+    /// Refuse to open more than `n` live sessions from one connection.
+    pub fn with_max_sessions(mut self, n: usize) -> Self {
+        self.max_sessions = n;
+        self
+    }
+
+    /// Close connections silent for `timeout` and drop sessions unused for as long.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Accept connections until the listener fails, one thread per client up to
+    /// the connection limit; clients over it get a `server_full` error.
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let mut stream = stream?;
+            if self.connections.fetch_add(1, Ordering::SeqCst) >= self.max_connections {
+                self.connections.fetch_sub(1, Ordering::SeqCst);
+                let full = Response::bare("hello").failed("server_full", format!("the server takes at most {} connections", self.max_connections));
+                let _ = writeln!(stream, "{}", full.to_json());
+                continue;
+            }
+            let server = Arc::clone(&self);
+            thread::spawn(move || {
+                let _ = server.connection(stream);
+                server.connections.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        Ok(())
+    }
+
+    /// Number of open sessions.
+    pub fn sessions(&self) -> usize { self.table().len() }
+
+    fn connection(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(self.idle_timeout))?;
+        let mut out = stream.try_clone()?;
+        writeln!(out, "{}", Response { version: Some(VERSION), ..Response::bare("hello") }.to_json())?;
+        let mut opened = Vec::new();
+        for line in BufReader::new(stream).lines() {
+            let line = match line {
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                    let idle = Response::bare("timeout").failed("idle_timeout", format!("no request for {} s", self.idle_timeout.as_secs()));
+                    writeln!(out, "{}", idle.to_json())?;
+                    break;
+                }
+                line => line?,
+            };
+            if line.trim().is_empty() { continue; }
+            let (resp, quit) = self.handle(&line, &mut opened);
+            writeln!(out, "{}", resp.to_json())?;
+            if quit { break; }
+        }
+        Ok(())
+    }
+
+    /// Answer one request line for a connection that opened the sessions in
+    /// `opened`; the flag is true when the client asked to quit.
+    pub fn handle(&self, line: &str, opened: &mut Vec<u64>) -> (Response, bool) {
+        let env = match serde_json::from_str::<Envelope>(line) {
+            Ok(env) => env,
+            Err(e) => return (Response::bare("invalid").failed("bad_request", e.to_string()), false),
+        };
+        match (env.op.as_str(), env.session) {
+            ("quit", _) => (Response { result: Some("bye"), ..Response::bare("quit") }, true),
+            ("new", None) => (self.open(line, opened), false),
+            ("close", Some(id)) => match self.table().remove(&id) {
+                Some(_) => (Response { session: Some(id), result: Some("closed"), ..Response::bare("close") }, false),
+                None => (unknown(id), false),
+            },
+            (_, None) => (Response::bare("invalid").failed("no_session", "this request needs a \"session\" id"), false),
+            (_, Some(id)) => {
+                let session = self.table().get_mut(&id).map(|slot| { slot.used = Instant::now(); Arc::clone(&slot.session) });
+                let Some(session) = session else { return (unknown(id), false) };
+                let resp = session.lock().unwrap_or_else(|e| e.into_inner()).handle(line);
+                (Response { session: Some(id), ..resp }, false)
+            }
+        }
+    }
+
+    fn open(&self, line: &str, opened: &mut Vec<u64>) -> Response {
+        let table = self.table();
+        opened.retain(|id| table.contains_key(id));
+        drop(table);
+        if opened.len() >= self.max_sessions {
+            return Response::bare("new").failed("too_many_sessions", format!("a connection may hold at most {} sessions", self.max_sessions));
+        }
+        let mut session = Session::new(self.template.clone());
+        let params = match serde_json::from_str::<Request>(line) {
+            Ok(Request::New(params)) => params,
+            Ok(_) => unreachable!("routed on op"),
+            Err(e) => return Response::bare("new").failed("bad_request", e.to_string()),
+        };
+        let resp = session.apply(Request::New(params));
+        if !resp.ok { return resp; }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.table().insert(id, Slot { session: Arc::new(Mutex::new(session)), used: Instant::now() });
+        opened.push(id);
+        Response { session: Some(id), ..resp }
+    }
+
+    // The session table, after dropping sessions idle for longer than the timeout.
+    fn table(&self) -> MutexGuard<'_, HashMap<u64, Slot>> {
+        // A panicking client thread must not take the whole server down.
+        let mut table = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        table.retain(|_, slot| slot.used.elapsed() < self.idle_timeout);
+        table
+    }
+}
+
+fn unknown(id: u64) -> Response {
+    Response::bare("invalid").failed("unknown_session", format!("no session {}", id))
+}
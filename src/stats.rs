@@ -0,0 +1,280 @@
+//! Lifetime player statistics, kept across sessions.
+//!
+//! Every finished single-player game is appended to a plain-text log after a
+//! `minesweeper-stats 1` header:
+//!
+//! ```text
+//! <player> <unix_date> <width> <height> <mines> <no_guess 0|1> <won 0|1> <time_ms> <3bv> <solved_3bv> <clicks> [tags]
+//! ```
+//!
+//! Tags are the board tags of the score table. Aggregates (win rates, streaks,
+//! times) are computed from the log when it is read, so it never goes stale.
+//! Lines that fail to parse are skipped, and a missing file is an empty log.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::date;
+use crate::engine::Board;
+use crate::paths;
+use crate::presets;
+use crate::scores::Category;
+
+const HEADER: &str = "minesweeper-stats 1";
+
+/// Upper bounds (seconds) and labels of the completion-time histogram buckets.
+const BUCKETS: [(u64, &str); 7] = [
+    (10, "<10s"), (30, "10-30s"), (60, "30-60s"), (120, "1-2m"), (300, "2-5m"), (600, "5-10m"), (u64::MAX, "10m+"),
+];
+
+/// Player name used when none is given: `$USER`, else `$USERNAME`, else `player`.
+pub fn default_player() -> String {
+    let name = env::var("USER").or_else(|_| env::var("USERNAME")).unwrap_or_default();
+    player_name(if name.trim().is_empty() { "player" } else { &name })
+}
+
+/// A name usable as one field of a log line: whitespace becomes `_`.
+pub fn player_name(name: &str) -> String {
+    name.trim().chars().map(|c| if c.is_whitespace() { '_' } else { c }).collect()
+}
+
+/// One finished game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    pub player: String,
+    /// Unix timestamp of the end of the game.
+    pub date: u64,
+    pub category: Category,
+    pub won: bool,
+    pub time: Duration,
+    pub bbbv: usize,
+    pub solved_bbbv: usize,
+    pub clicks: usize,
+}
+
+impl GameRecord {
+    /// A record for a won or lost board; `None` while the game is still going.
+    pub fn from_board(board: &Board, player: &str) -> Option<Self> {
+        if board.alive() && !board.won() { return None; }
+        let stats = board.stats();
+        Some(Self {
+            player: player_name(player),
+            date: date::now_unix(),
+            category: Category::of(board),
+            won: board.won(),
+            time: stats.elapsed,
+            bbbv: stats.bbbv,
+            solved_bbbv: stats.solved_bbbv,
+            clicks: stats.clicks.total(),
+        })
+    }
+
+    /// Solved 3BV per second.
+    pub fn bbbv_per_sec(&self) -> Option<f64> {
+        let secs = self.time.as_secs_f64();
+        (secs > 0.0).then(|| self.solved_bbbv as f64 / secs)
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let f: Vec<&str> = line.split_whitespace().collect();
+        if f.len() < 11 { return None; }
+        let [player, when] = f[..2] else { return None };
+        let [won, ms, bbbv, solved, clicks] = f[6..11] else { return None };
+        Some(Self {
+            player: player.to_string(),
+            date: when.parse().ok()?,
+            category: Category::parse(&f[2..6], &f[11..])?,
+            won: match won { "0" => false, "1" => true, _ => return None },
+            time: Duration::from_millis(ms.parse().ok()?),
+            bbbv: bbbv.parse().ok()?,
+            solved_bbbv: solved.parse().ok()?,
+            clicks: clicks.parse().ok()?,
+        })
+    }
+
+    fn line(&self) -> String {
+        let c = &self.category;
+        let mut line = format!(
+            "{} {} {} {} {} {} {} {} {} {} {}",
+            self.player, self.date, c.width, c.height, c.mines, c.no_guess as u8,
+            self.won as u8, self.time.as_millis(), self.bbbv, self.solved_bbbv, self.clicks
+        );
+        c.write_tags(&mut line);
+        line
+    }
+}
+
+/// Aggregates for one player, serializable for `stats --json`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub player: String,
+    pub played: usize,
+    pub won: usize,
+    /// Wins per game played, 0 to 1.
+    pub win_rate: f64,
+    /// Wins since the last loss.
+    pub current_streak: usize,
+    pub best_streak: usize,
+    /// Mean time of the won games.
+    pub average_time_ms: Option<u64>,
+    pub best_bbbv_per_sec: Option<f64>,
+    pub boards: Vec<BoardSummary>,
+    /// Won games by completion time.
+    pub histogram: Vec<Bucket>,
+}
+
+/// Aggregates for one board configuration.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BoardSummary {
+    /// Preset name for the built-in sizes, else the configuration label.
+    pub board: String,
+    pub played: usize,
+    pub won: usize,
+    pub win_rate: f64,
+    pub best_time_ms: Option<u64>,
+    pub average_time_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Bucket {
+    pub label: &'static str,
+    pub count: usize,
+}
+
+pub struct StatsLog {
+    path: PathBuf,
+    games: Vec<GameRecord>,
+}
+
+impl StatsLog {
+    pub fn default_path() -> PathBuf { paths::data_dir().join("stats.txt") }
+
+    /// Load the log; a missing or unreadable file yields an empty log.
+    pub fn load(path: &Path) -> Self {
+        let games = fs::read_to_string(path)
+            .map(|text| text.lines().filter(|l| *l != HEADER).filter_map(GameRecord::parse).collect())
+            .unwrap_or_default();
+        Self { path: path.to_path_buf(), games }
+    }
+
+    /// Add a game and append it to the file, writing the header for a new file.
+    pub fn record(&mut self, game: GameRecord) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() { fs::create_dir_all(dir)?; }
+        let fresh = fs::metadata(&self.path).map_or(true, |m| m.len() == 0);
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        if fresh { writeln!(file, "{}", HEADER)?; }
+        writeln!(file, "{}", game.line())?;
+        self.games.push(game);
+        Ok(())
+    }
+
+    /// All games, oldest first.
+    pub fn games(&self) -> &[GameRecord] { &self.games }
+
+    /// Players in order of their first game.
+    pub fn players(&self) -> Vec<String> {
+        let mut players: Vec<String> = Vec::new();
+        for g in &self.games {
+            if !players.contains(&g.player) { players.push(g.player.clone()); }
+        }
+        players
+    }
+
+    /// Aggregates over one player's games.
+    pub fn summary(&self, player: &str) -> Summary {
+        let games: Vec<&GameRecord> = self.games.iter().filter(|g| g.player == player).collect();
+        let wins: Vec<&GameRecord> = games.iter().copied().filter(|g| g.won).collect();
+        let (mut current_streak, mut best_streak) = (0, 0);
+        for g in &games {
+            current_streak = if g.won { current_streak + 1 } else { 0 };
+            best_streak = best_streak.max(current_streak);
+        }
+        let mut categories: Vec<Category> = games.iter().map(|g| g.category).collect();
+        categories.sort();
+        categories.dedup();
+        let boards = categories.into_iter().map(|c| {
+            let played = games.iter().filter(|g| g.category == c).count();
+            let won: Vec<&GameRecord> = wins.iter().copied().filter(|g| g.category == c).collect();
+            BoardSummary {
+                board: board_name(c),
+                played,
+                won: won.len(),
+                win_rate: rate(won.len(), played),
+                best_time_ms: won.iter().map(|g| g.time.as_millis() as u64).min(),
+                average_time_ms: average_ms(&won),
+            }
+        }).collect();
+        let histogram = BUCKETS.iter().enumerate().map(|(i, &(upper, label))| {
+            let lower = if i == 0 { 0 } else { BUCKETS[i - 1].0 };
+            Bucket { label, count: wins.iter().filter(|g| (lower..upper).contains(&g.time.as_secs())).count() }
+        }).collect();
+        Summary {
+            player: player.to_string(),
+            played: games.len(),
+            won: wins.len(),
+            win_rate: rate(wins.len(), games.len()),
+            current_streak,
+            best_streak,
+            average_time_ms: average_ms(&wins),
+            best_bbbv_per_sec: wins.iter().filter_map(|g| g.bbbv_per_sec()).reduce(f64::max),
+            boards,
+            histogram,
+        }
+    }
+
+    /// Summaries for every player, or only `player` when given.
+    pub fn summaries(&self, player: Option<&str>) -> Vec<Summary> {
+        match player {
+            Some(p) => vec![self.summary(p)],
+            None => self.players().iter().map(|p| self.summary(p)).collect(),
+        }
+    }
+}
+
+/// A player's dashboard as text: totals, a per-board table and the histogram.
+pub fn render(s: &Summary) -> String {
+    let secs = |ms: Option<u64>| ms.map_or("-".to_string(), |ms| format!("{:.1}s", ms as f64 / 1000.0));
+    let mut out = format!("Player {}\n", s.player);
+    if s.played == 0 { out.push_str("  No games yet.\n"); return out; }
+    let _ = writeln!(out, "  Games {}  Won {} ({:.0}%)  Streak {} (best {})", s.played, s.won, 100.0 * s.win_rate, s.current_streak, s.best_streak);
+    let _ = writeln!(
+        out, "  Average win {}  Best 3BV/s {}\n", secs(s.average_time_ms),
+        s.best_bbbv_per_sec.map_or("-".to_string(), |v| format!("{:.2}", v)),
+    );
+    let _ = writeln!(out, "  {:<28} {:>6} {:>5} {:>5} {:>8} {:>8}", "Board", "Played", "Won", "Win%", "Best", "Average");
+    for b in &s.boards {
+        let _ = writeln!(
+            out, "  {:<28} {:>6} {:>5} {:>4.0}% {:>8} {:>8}",
+            b.board, b.played, b.won, 100.0 * b.win_rate, secs(b.best_time_ms), secs(b.average_time_ms),
+        );
+    }
+    let widest = s.histogram.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+    out.push_str("\n  Completion times\n");
+    for b in &s.histogram {
+        let _ = writeln!(out, "  {:>7} {:<20} {}", b.label, "#".repeat((b.count * 20).div_ceil(widest)), b.count);
+    }
+    out
+}
+
+fn board_name(c: Category) -> String {
+    let preset = presets::builtin().into_iter().find(|p| (p.width, p.height, p.mines) == (c.width, c.height, c.mines));
+    match preset {
+        Some(p) if !c.custom() => format!("{}{}", p.name, if c.no_guess { " no-guess" } else { "" }),
+        _ => c.label(),
+    }
+}
+
+fn rate(n: usize, of: usize) -> f64 {
+    if of == 0 { 0.0 } else { n as f64 / of as f64 }
+}
+
+fn average_ms(games: &[&GameRecord]) -> Option<u64> {
+    if games.is_empty() { return None; }
+    Some(games.iter().map(|g| g.time.as_millis() as u64).sum::<u64>() / games.len() as u64)
+}
@@ -1,4 +1,5 @@
 use std::io;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
@@ -8,22 +9,168 @@ use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph};
 use ratatui::Terminal;
 
-use crate::engine::Board;
+use crate::daily::{Attempt, Daily, DailyLog};
+use crate::engine::replay::{self, Player, Replay, Step};
+use crate::engine::solver::{self, HintKind, Probabilities};
+use crate::engine::mask::Mask;
+use crate::engine::rng::SeedSpec;
+use crate::engine::{save, Action, Board, Error, Topology};
+use crate::presets::{self, Preset};
+use crate::scores::{Category, Score, ScoreTable};
+use crate::stats::{self, GameRecord, StatsLog};
 
-pub fn run_tui(width: usize, height: usize, mines: usize, seed: u64) -> io::Result<()> {
+pub mod flags;
+pub mod race;
+pub use flags::run_flags;
+pub use race::run_race;
+
+/// Settings for a TUI session.
+pub struct TuiOptions {
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub seed: u64,
+    /// Generate boards that never require a guess.
+    pub no_guess: bool,
+    /// Square or hexagonal grid for new games.
+    pub topology: Topology,
+    /// Join opposite edges of new boards (a torus).
+    pub wrap: bool,
+    /// Let `f` cycle through a `?` marker after the flag.
+    pub question_marks: bool,
+    /// Shape for new boards; `None` for a full rectangle.
+    pub mask: Option<Mask>,
+    /// File used by the `S`/`L` keys.
+    pub save_path: PathBuf,
+    /// Start from this board (e.g. one loaded with `--load`) instead of a new game.
+    pub board: Option<Board>,
+    /// Record the current game into this replay file.
+    pub record: Option<PathBuf>,
+    /// Name finished games are recorded under in the lifetime statistics.
+    pub player: String,
+    /// Today's challenge when `board` is its board: undo and new games are off,
+    /// and the result goes into the daily log.
+    pub daily: Option<Daily>,
+}
+
+// Extra state drawn over the board: a status message and optional overlays.
+#[derive(Default)]
+struct Overlay {
+    message: Option<String>,
+    heatmap: bool,
+    // Centered window (title, text) that swallows the next key press.
+    popup: Option<(String, String)>,
+    // Cell suggested by the last `?` hint.
+    hint: Option<(usize, usize)>,
+    // Cached for the current board state; cleared on every input event.
+    probs: Option<Probabilities>,
+    // Board cell drawn at the top-left corner; wrapped boards can scroll the torus.
+    offset: (usize, usize),
+    // New-game menu opened with `m`; takes all keys while open.
+    menu: Option<Menu>,
+    // Mines claimed in a Flags match as (x, y, player), drawn in the player's color.
+    claims: Vec<(usize, usize, usize)>,
+}
+
+// Colors for player 1 and player 2 in the two-player modes.
+const PLAYER_COLORS: [Color; 2] = [Color::Blue, Color::Red];
+
+struct Menu {
+    presets: Vec<Preset>,
+    selected: usize,
+}
+
+type Term = Terminal<CrosstermBackend<io::Stdout>>;
+
+fn setup_terminal() -> io::Result<(Term, TermGuard)> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     stdout.execute(EnterAlternateScreen)?;
     stdout.execute(EnableMouseCapture)?;
-    let _guard = TermGuard;
+    let guard = TermGuard;
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    Ok((Terminal::new(backend)?, guard))
+}
 
-    let mut board = Board::new(width, height, mines, seed).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-    let mut cursor = (0usize, 0usize);
+// Replay being captured for `--record`; restarted whenever a new game begins.
+struct Recorder {
+    path: PathBuf,
+    replay: Replay,
+    start: Instant,
+}
+
+impl Recorder {
+    fn new(path: PathBuf, board: &Board) -> Self {
+        Self { path, replay: Replay::new(board), start: Instant::now() }
+    }
+
+    fn write(&self) -> Option<String> {
+        self.replay.write(&self.path).err().map(|e| format!("Cannot write replay: {}", e))
+    }
+}
+
+// Apply a player step to the board, recording it when a recorder is active.
+fn play(board: &mut Board, recorder: &mut Option<Recorder>, step: Step) -> Option<String> {
+    let was_over = !board.alive() || board.won();
+    replay::apply(board, step);
+    let rec = recorder.as_mut()?;
+    rec.replay.push(rec.start.elapsed(), step);
+    let over = !board.alive() || board.won();
+    if over && !was_over { rec.write() } else { None }
+}
+
+// Parameters for the boards started with `n` and from the new-game menu.
+struct NewGame {
+    width: usize,
+    height: usize,
+    mines: usize,
+    seed: u64,
+    no_guess: bool,
+    topology: Topology,
+    wrap: bool,
+    question_marks: bool,
+    mask: Option<Mask>,
+}
+
+impl NewGame {
+    fn board(&self) -> Result<Board, Error> {
+        let board = match &self.mask {
+            Some(mask) => Board::from_mask(mask.clone(), self.mines, self.seed)?,
+            None => Board::new(self.width, self.height, self.mines, self.seed)?,
+        };
+        Ok(board.with_topology(self.topology)?.with_wrap(self.wrap)?.with_no_guess(self.no_guess).with_question_marks(self.question_marks))
+    }
+
+    // Adopt a loaded board's shape so `n` restarts with the same settings.
+    fn adopt(&mut self, board: &Board) {
+        (self.width, self.height, self.mines) = (board.width(), board.height(), board.mines());
+        (self.topology, self.wrap, self.mask) = (board.topology(), board.wrap(), board.mask().cloned());
+        self.question_marks = board.question_marks();
+    }
+}
+
+pub fn run_tui(opts: TuiOptions) -> Result<(), Error> {
+    let TuiOptions { width, height, mines, seed, no_guess, topology, wrap, question_marks, mask, save_path, board: loaded, record, player, daily } = opts;
+    let mut settings = NewGame { width, height, mines, seed, no_guess, topology, wrap, question_marks, mask };
+    let (mut terminal, _guard) = setup_terminal()?;
+
+    let mut board = match loaded {
+        Some(b) => b,
+        None => settings.board()?,
+    };
+    let mut overlay = Overlay::default();
+    // Whether the current board's win has already been submitted to the score table;
+    // daily wins only go into the daily log, so their popup is the only one.
+    let mut scored = board.won() || daily.is_some();
+    // Whether the current game already went into the lifetime statistics.
+    let mut logged = !board.alive() || board.won();
+    // A daily challenge stays locked to its one board, even after it ends.
+    let mut daily_recorded = false;
+    let mut recorder = record.map(|path| Recorder::new(path, &board));
+    let mut cursor = first_playable(&board);
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_millis(250);
     let autodemo = std::env::var("MINESWEEPER_TUI_AUTODEMO").ok().is_some();
@@ -31,45 +178,160 @@ pub fn run_tui(width: usize, height: usize, mines: usize, seed: u64) -> io::Resu
 
     let mut last_inner_board = Rect::default();
     let res = loop {
-        terminal.draw(|f| { last_inner_board = ui(f, &board, cursor); })?;
+        if overlay.heatmap && overlay.probs.is_none() { overlay.probs = Some(solver::probabilities(&board)); }
+        // Keep the view inside the board after a resize or a new game.
+        let view = visible(last_inner_board, &board);
+        scroll(&mut overlay.offset, 0, 0, view, &board);
+        terminal.draw(|f| { last_inner_board = ui(f, &board, cursor, &overlay); })?;
+        let view = visible(last_inner_board, &board);
 
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if event::poll(timeout)? {
-            match event::read()? {
+            let ev = event::read()?;
+            if matches!(ev, Event::Key(_) | Event::Mouse(_)) { overlay.probs = None; }
+            match ev {
+                Event::Key(key) if key.kind == KeyEventKind::Press && overlay.popup.is_some() => { overlay.popup = None; }
+                Event::Key(key) if key.kind == KeyEventKind::Press && overlay.menu.is_some() => {
+                    let menu = overlay.menu.as_mut().unwrap();
+                    let n = menu.presets.len();
+                    let mut chosen = None;
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('m') => overlay.menu = None,
+                        KeyCode::Up | KeyCode::Char('k') => menu.selected = (menu.selected + n - 1) % n,
+                        KeyCode::Down | KeyCode::Char('j') => menu.selected = (menu.selected + 1) % n,
+                        KeyCode::Enter | KeyCode::Char(' ') => chosen = Some(menu.selected),
+                        KeyCode::Char(c @ '1'..='9') if (c as usize - '1' as usize) < n => chosen = Some(c as usize - '1' as usize),
+                        _ => {}
+                    }
+                    if let Some(i) = chosen {
+                        let p = overlay.menu.take().unwrap().presets.swap_remove(i);
+                        let next = NewGame { width: p.width, height: p.height, mines: p.mines, mask: None, ..settings };
+                        match next.board() {
+                            Ok(b) => {
+                                board = b;
+                                settings = next;
+                                scored = false;
+                                logged = false;
+                                overlay.offset = (0, 0);
+                                cursor = first_playable(&board);
+                                if let Some(rec) = recorder.as_mut() { *rec = Recorder::new(rec.path.clone(), &board); }
+                                overlay.message = Some(format!("New game: {}", p));
+                            }
+                            Err(e) => overlay.message = Some(format!("Cannot start {}: {}", p.name, e)),
+                        }
+                    }
+                }
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    overlay.message = None;
+                    overlay.hint = None;
+                    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
                     let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+                    let before = cursor;
+                    // Page sizes: a whole view, or half of one.
+                    let (pw, ph) = (view.0.max(1) as isize, view.1.max(1) as isize);
+                    let page = match key.code {
+                        KeyCode::PageUp => Some((0, -ph)),
+                        KeyCode::PageDown => Some((0, ph)),
+                        KeyCode::Home => Some((-pw, 0)),
+                        KeyCode::End => Some((pw, 0)),
+                        KeyCode::Char('u') if ctrl => Some((0, -(ph / 2).max(1))),
+                        KeyCode::Char('d') if ctrl => Some((0, (ph / 2).max(1))),
+                        KeyCode::Char('<') => Some((-(pw / 2).max(1), 0)),
+                        KeyCode::Char('>') => Some(((pw / 2).max(1), 0)),
+                        _ => None,
+                    };
+                    if let Some((dx, dy)) = page {
+                        scroll(&mut overlay.offset, dx, dy, view, &board);
+                        cursor = jump_cursor(&board, cursor, dx, dy);
+                    }
                     match key.code {
+                        _ if page.is_some() => {}
                         KeyCode::Char('q') | KeyCode::Esc => break Ok(()),
-                        KeyCode::Char('h') | KeyCode::Left => {
-                            if cursor.0 > 0 { cursor.0 -= 1; }
+                        KeyCode::Char('u' | 'n' | 'R' | 'm' | 'L') if daily.is_some() => { overlay.message = Some("Daily challenge: one attempt, no undo or new games".into()); }
+                        KeyCode::Char('r') if ctrl && daily.is_some() => { overlay.message = Some("Daily challenge: one attempt, no redo".into()); }
+                        KeyCode::Char('?' | 'p' | 'S') if daily.is_some() => { overlay.message = Some("Daily challenge: no hints, heatmap or saves".into()); }
+                        KeyCode::Char('r') if ctrl => { overlay.message = play(&mut board, &mut recorder, Step::Redo); }
+                        KeyCode::Char('u') => { overlay.message = play(&mut board, &mut recorder, Step::Undo); }
+                        KeyCode::Char('S') => {
+                            overlay.message = Some(match save::write(&board, &save_path) {
+                                Ok(()) => format!("Saved to {}", save_path.display()),
+                                Err(e) => format!("Save failed: {}", e),
+                            });
                         }
-                        KeyCode::Char('l') | KeyCode::Right => {
-                            if cursor.0 + 1 < board.width() { cursor.0 += 1; }
+                        KeyCode::Char('L') => {
+                            overlay.message = Some(match save::read(&save_path) {
+                                Ok(b) => {
+                                    board = b;
+                                    scored = board.won();
+                                    logged = !board.alive() || board.won();
+                                    if let Some(rec) = recorder.take() { let _ = rec.write(); }
+                                    settings.adopt(&board);
+                                    overlay.offset = (0, 0);
+                                    cursor = first_playable(&board);
+                                    format!("Loaded {}", save_path.display())
+                                }
+                                Err(e) => format!("Load failed: {}", e),
+                            });
                         }
-                        KeyCode::Char('k') | KeyCode::Up => {
-                            if cursor.1 > 0 { cursor.1 -= 1; }
-                        }
-                        KeyCode::Char('j') | KeyCode::Down => {
-                            if cursor.1 + 1 < board.height() { cursor.1 += 1; }
+                        // Shift+arrows scroll the view: around the torus on wrapped boards,
+                        // up to the edges on boards bigger than the terminal.
+                        KeyCode::Left if shift => scroll(&mut overlay.offset, -1, 0, view, &board),
+                        KeyCode::Right if shift => scroll(&mut overlay.offset, 1, 0, view, &board),
+                        KeyCode::Up if shift => scroll(&mut overlay.offset, 0, -1, view, &board),
+                        KeyCode::Down if shift => scroll(&mut overlay.offset, 0, 1, view, &board),
+                        KeyCode::Char('h') | KeyCode::Left => { cursor = move_cursor(&board, cursor, -1, 0); }
+                        KeyCode::Char('l') | KeyCode::Right => { cursor = move_cursor(&board, cursor, 1, 0); }
+                        KeyCode::Char('k') | KeyCode::Up => { cursor = move_cursor(&board, cursor, 0, -1); }
+                        KeyCode::Char('j') | KeyCode::Down => { cursor = move_cursor(&board, cursor, 0, 1); }
+                        KeyCode::Char('f') => { overlay.message = play(&mut board, &mut recorder, Step::Move(Action::ToggleFlag(cursor.0, cursor.1))); }
+                        KeyCode::Char('p') => { overlay.heatmap = !overlay.heatmap; }
+                        KeyCode::Char('t') => { overlay.popup = Some(stats_screen(&player)); }
+                        KeyCode::Char('?') => {
+                            match solver::hint(&board) {
+                                Some(h) => {
+                                    let verb = match h.kind { HintKind::Safe => "Reveal", HintKind::Mine => "Flag", HintKind::Guess(_) => "Guess" };
+                                    overlay.message = Some(format!("{}: {}", verb, h.reason));
+                                    overlay.hint = Some((h.x, h.y));
+                                    cursor = (h.x, h.y);
+                                }
+                                None => overlay.message = Some("No hint: the game is over".into()),
+                            }
                         }
-                        KeyCode::Char('f') => { let _ = board.toggle_flag(cursor.0, cursor.1); }
+                        KeyCode::Char('c') => { overlay.message = play(&mut board, &mut recorder, Step::Move(Action::Chord(cursor.0, cursor.1))); }
                         KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('r') => {
-                            let _ = board.reveal(cursor.0, cursor.1);
+                            overlay.message = play(&mut board, &mut recorder, Step::Move(Action::Reveal(cursor.0, cursor.1)));
+                        }
+                        KeyCode::Char('m') => {
+                            let path = presets::user_path();
+                            let (presets, errors) = presets::load(&path);
+                            let current = (board.width(), board.height(), board.mines());
+                            let selected = presets.iter().position(|p| (p.width, p.height, p.mines) == current).unwrap_or(0);
+                            if let Some(e) = errors.first() { overlay.message = Some(format!("{}: {}", path.display(), e)); }
+                            overlay.menu = Some(Menu { presets, selected });
+                        }
+                        KeyCode::Char('n') | KeyCode::Char('R') => {
+                            if let Ok(b) = settings.board() {
+                                board = b;
+                                scored = false;
+                                logged = false;
+                                if let Some(rec) = recorder.as_mut() { *rec = Recorder::new(rec.path.clone(), &board); }
+                            }
                         }
-                        KeyCode::Char('n') => { if let Ok(b) = Board::new(width, height, mines, seed) { board = b; } }
-                        KeyCode::Char('R') if shift => { if let Ok(b) = Board::new(width, height, mines, seed) { board = b; } }
                         _ => {}
                     }
+                    if cursor != before { follow(&mut overlay.offset, cursor, view, &board); }
                 }
                 Event::Mouse(m) => {
                     // Map mouse to cell coordinates within the inner board area
                     if let MouseEventKind::Down(btn) = m.kind {
-                        if let Some((cx, cy)) = pos_to_cell(m.column, m.row, last_inner_board, board.width() as u16, board.height() as u16) {
-                            match btn {
-                                MouseButton::Left => { let _ = board.reveal(cx as usize, cy as usize); }
-                                MouseButton::Right => { let _ = board.toggle_flag(cx as usize, cy as usize); }
-                                MouseButton::Middle => { /* reserved for future chording */ }
-                            }
+                        if let Some((cx, cy)) = pos_to_cell(m.column, m.row, last_inner_board, &board, overlay.offset) {
+                            let (cx, cy) = (cx as usize, cy as usize);
+                            let action = match btn {
+                                MouseButton::Left => Action::Reveal(cx, cy),
+                                MouseButton::Right => Action::ToggleFlag(cx, cy),
+                                MouseButton::Middle => Action::Chord(cx, cy),
+                            };
+                            overlay.message = play(&mut board, &mut recorder, Step::Move(action));
                         }
                     }
                 }
@@ -77,6 +339,22 @@ pub fn run_tui(width: usize, height: usize, mines: usize, seed: u64) -> io::Resu
                 _ => {}
             }
         }
+        if (!board.alive() || board.won()) && !logged {
+            logged = true;
+            if let Some(game) = GameRecord::from_board(&board, &player) {
+                if let Err(e) = StatsLog::load(&StatsLog::default_path()).record(game) { overlay.message = Some(format!("Cannot save stats: {}", e)); }
+            }
+        }
+        if board.won() && !scored {
+            scored = true;
+            overlay.popup = Some(record_win(&board));
+        }
+        if !board.alive() || board.won() {
+            if let Some(d) = daily.as_ref().filter(|_| !daily_recorded) {
+                daily_recorded = true;
+                overlay.popup = Some(record_daily(d, &board));
+            }
+        }
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
             if autodemo {
@@ -93,28 +371,116 @@ pub fn run_tui(width: usize, height: usize, mines: usize, seed: u64) -> io::Resu
         }
     };
 
+    // Leaving an unfinished daily challenge gives it up.
+    if let Some(d) = daily.as_ref().filter(|_| !daily_recorded) { record_daily(d, &board); }
+    if let Some(msg) = recorder.and_then(|rec| rec.write()) { eprintln!("{}", msg); }
     // teardown via guard; just ensure cursor visible
     terminal.show_cursor()?;
     res
 }
 
-fn ui(f: &mut ratatui::Frame, board: &Board, cursor: (usize, usize)) -> Rect {
+// Submit a win to the score table and build the leaderboard popup.
+fn record_win(board: &Board) -> (String, String) {
+    let path = ScoreTable::default_path();
+    let mut table = ScoreTable::load(&path);
+    let category = Category::of(board);
+    let mut text = format!("You won in {:.3}s!\n{}\n", board.elapsed().as_secs_f64(), board.stats());
+    if let Some(spec) = SeedSpec::of(board) { text.push_str(&format!("Board {}\n", spec)); }
+    text.push('\n');
+    if let Some(score) = Score::from_board(board) {
+        match table.submit(score) {
+            Some(rank) => text.push_str(&format!("New best time #{}\n\n", rank)),
+            None => text.push_str("Not a top time this round.\n\n"),
+        }
+        if let Err(e) = table.save() { text.push_str(&format!("(could not save scores: {})\n\n", e)); }
+    }
+    text.push_str(&table.render_category(category));
+    text.push_str("\nPress any key");
+    ("Leaderboard".into(), text)
+}
+
+// Record the daily attempt and build the share popup.
+fn record_daily(daily: &Daily, board: &Board) -> (String, String) {
+    let attempt = Attempt::from_board(&daily.date, board);
+    let mut text = attempt.share();
+    if let Err(e) = DailyLog::load(&DailyLog::default_path()).record(attempt) { text.push_str(&format!("\n(could not save the result: {})\n", e)); }
+    text.push_str("\nPress any key");
+    ("Daily challenge".into(), text)
+}
+
+// Lifetime statistics of the player as a popup.
+fn stats_screen(player: &str) -> (String, String) {
+    let mut text = stats::render(&StatsLog::load(&StatsLog::default_path()).summary(player));
+    text.push_str("\nPress any key");
+    ("Statistics".into(), text)
+}
+
+/// Play back a recorded game: Space play/pause, Right/`s` step, `+`/`-` speed, `q` quit.
+pub fn run_replay(mut player: Player) -> Result<(), Error> {
+    let (mut terminal, _guard) = setup_terminal()?;
+    let mut playing = false;
+    let mut speed = 1.0f64;
+    let mut cursor = (0usize, 0usize);
+    let mut last_step = Instant::now();
+    let res = loop {
+        let status = format!(
+            "Replay {}/{} • {} • {}x — Space play/pause • →/s step • +/- speed • q quit",
+            player.position(), player.len(), if playing { "playing" } else if player.finished() { "finished" } else { "paused" }, speed,
+        );
+        let overlay = Overlay { message: Some(status), ..Overlay::default() };
+        terminal.draw(|f| { ui(f, player.board(), cursor, &overlay); })?;
+
+        let delay = player.next_delay().map(|d| d.div_f64(speed));
+        let timeout = match delay {
+            Some(d) if playing => d.saturating_sub(last_step.elapsed()).min(Duration::from_millis(250)),
+            _ => Duration::from_millis(250),
+        };
+        let mut advance = false;
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press { continue; }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => break Ok(()),
+                    KeyCode::Char(' ') => { playing = !playing; last_step = Instant::now(); }
+                    KeyCode::Right | KeyCode::Char('s') | KeyCode::Char('.') => { playing = false; advance = true; }
+                    KeyCode::Char('+') | KeyCode::Char('=') => speed = (speed * 2.0).min(64.0),
+                    KeyCode::Char('-') => speed = (speed / 2.0).max(1.0 / 64.0),
+                    _ => {}
+                }
+            }
+        }
+        if playing && delay.is_some_and(|d| last_step.elapsed() >= d) { advance = true; }
+        if advance {
+            if let Some(ev) = player.step() {
+                if let Step::Move(Action::Reveal(x, y) | Action::ToggleFlag(x, y) | Action::Chord(x, y)) = ev.step { cursor = (x, y); }
+                last_step = Instant::now();
+            }
+            if player.finished() { playing = false; }
+        }
+    };
+    terminal.show_cursor()?;
+    res
+}
+
+fn ui(f: &mut ratatui::Frame, board: &Board, cursor: (usize, usize), overlay: &Overlay) -> Rect {
     let root = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(5),
-            Constraint::Length(2),
+            Constraint::Length(3),
         ])
         .split(f.size());
 
     // Header
-    let status = if !board.alive() {
-        "Boom! You hit a mine — q to quit, n to restart"
+    let status = if let Some(msg) = overlay.message.as_deref() {
+        msg
+    } else if !board.alive() {
+        "Boom! You hit a mine — u to undo, q to quit, n to restart"
     } else if board.won() {
         "You won! q to quit, n to restart"
     } else {
-        "Mouse: left=reveal, right=flag • Arrows/HJKL move • Enter/Space reveal • f flag • n new • q quit"
+        "Mouse: left=reveal, right=flag, middle=chord • Arrows/HJKL move • Enter/Space reveal • f flag • c chord • ? hint • p heatmap • t stats • u undo • ^R redo • S/L save/load • n new • m menu • q quit"
     };
     let header = Paragraph::new(status)
         .style(Style::default().fg(Color::Cyan))
@@ -122,63 +488,165 @@ fn ui(f: &mut ratatui::Frame, board: &Board, cursor: (usize, usize)) -> Rect {
         .block(Block::default().borders(Borders::ALL).title("Minesweeper"));
     f.render_widget(header, root[0]);
 
-    // Board area
-    let area = centered_grid_area(root[1], board.width() as u16, board.height() as u16);
+    // Board area, with a minimap beside it when the board does not fit
+    let (grid_w, grid_h) = grid_size(board);
+    let mut board_parent = root[1];
+    if grid_w > root[1].width || grid_h > root[1].height {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(10), Constraint::Length(MAP_COLS as u16 + 2)])
+            .split(root[1]);
+        board_parent = cols[0];
+        let view = visible(inner_area(centered_grid_area(board_parent, board)), board);
+        draw_minimap(f, cols[1], board, cursor, overlay.offset, view);
+    }
+    let area = centered_grid_area(board_parent, board);
     // Draw the board and compute the inner area used by cells (inside borders)
     let inner = inner_area(area);
-    draw_board(f, board, area, cursor);
+    draw_board(f, board, area, cursor, overlay);
 
-    let footer = Paragraph::new(format!("Size: {}x{}  Mines: {}", board.width(), board.height(), board.mines()))
+    let heat = match &overlay.probs {
+        Some(p) if overlay.heatmap => format!("  Heatmap: {}", if p.exact() { "exact" } else { "approx" }),
+        _ => String::new(),
+    };
+    let wrap = if board.wrap() { "  Wraps (Shift+arrows scroll)" } else if grid_w > root[1].width || grid_h > root[1].height { "  PgUp/PgDn/Home/End scroll" } else { "" };
+    let face = if !board.alive() { "😵" } else if board.won() { "😎" } else { "🙂" };
+    // Once the game is over the board details give way to its metrics.
+    let details = if !board.alive() || board.won() { board.stats().to_string() } else {
+        format!("Size: {}x{}  Mines: {}{}{}", board.width(), board.height(), board.mines(), wrap, heat)
+    };
+    let footer = Paragraph::new(format!(
+        "💣 {:03}   {}   ⏱ {:03}     {}",
+        board.mines_left(), face, board.elapsed().as_secs().min(999), details,
+    ))
         .style(Style::default().fg(Color::DarkGray))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(footer, root[2]);
+
+    if let Some((title, text)) = &overlay.popup {
+        let lines = text.lines().count() as u16 + 2;
+        let cols = text.lines().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 4;
+        let area = f.size();
+        let w = cols.min(area.width);
+        let h = lines.min(area.height);
+        let rect = Rect { x: area.x + (area.width - w) / 2, y: area.y + (area.height - h) / 2, width: w, height: h };
+        f.render_widget(Clear, rect);
+        let popup = Paragraph::new(text.as_str()).block(Block::default().borders(Borders::ALL).title(title.as_str()));
+        f.render_widget(popup, rect);
+    }
+    if let Some(menu) = &overlay.menu { draw_menu(f, menu); }
     inner
 }
 
-fn centered_grid_area(parent: Rect, cols: u16, rows: u16) -> Rect {
+// The new-game menu: one line per preset with the selection highlighted.
+fn draw_menu(f: &mut ratatui::Frame, menu: &Menu) {
+    let mut lines: Vec<Line> = menu.presets.iter().enumerate().map(|(i, p)| {
+        let text = format!(" {} {:<14} {:>9} ", if i < 9 { (b'1' + i as u8) as char } else { ' ' }, p.name, p.spec());
+        let style = if i == menu.selected { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() };
+        Line::from(Span::styled(text, style))
+    }).collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(" ↑/↓ select • Enter start • Esc cancel", Style::default().fg(Color::DarkGray))));
+    let cols = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 2;
+    let area = f.size();
+    let w = cols.min(area.width);
+    let h = (lines.len() as u16 + 2).min(area.height);
+    let rect = Rect { x: area.x + (area.width - w) / 2, y: area.y + (area.height - h) / 2, width: w, height: h };
+    f.render_widget(Clear, rect);
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("New game")), rect);
+}
+
+// Size of the whole board including its border.
+fn grid_size(board: &Board) -> (u16, u16) {
     let cell_w = 2; // one char + one space
     let cell_h = 1;
-    let grid_w = cols * cell_w;
-    let grid_h = rows * cell_h;
+    // Odd hex rows are shifted right by half a cell.
+    let w = (board.width() as u16).saturating_mul(cell_w).saturating_add(hex_shift(board, 1) + 2);
+    let h = (board.height() as u16).saturating_mul(cell_h).saturating_add(2);
+    (w, h)
+}
+
+// The board's rectangle centered in `parent`; boards bigger than `parent` get
+// all of it and show a scrolled view.
+fn centered_grid_area(parent: Rect, board: &Board) -> Rect {
+    let (grid_w, grid_h) = grid_size(board);
     let x = parent.x.saturating_add((parent.width.saturating_sub(grid_w)) / 2);
     let y = parent.y.saturating_add((parent.height.saturating_sub(grid_h)) / 2);
     Rect { x, y, width: grid_w.min(parent.width), height: grid_h.min(parent.height) }
 }
 
-fn draw_board(f: &mut ratatui::Frame, board: &Board, area: Rect, cursor: (usize, usize)) {
+fn draw_board(f: &mut ratatui::Frame, board: &Board, area: Rect, cursor: (usize, usize), overlay: &Overlay) {
     // Build lines of text representing each row.
-    let mut lines: Vec<Line> = Vec::with_capacity(board.height());
-    for y in 0..board.height() {
-        let mut spans: Vec<Span> = Vec::with_capacity(board.width() * 2);
-        for x in 0..board.width() {
+    // Rows and columns are drawn starting from `overlay.offset`; on a wrapped board
+    // the seams where the last row/column meets the first are marked.
+    let (w, h) = (board.width(), board.height());
+    let (cols, rows) = visible(inner_area(area), board);
+    let mut lines: Vec<Line> = Vec::with_capacity(rows);
+    for dy in 0..rows {
+        let y = (dy + overlay.offset.1) % h;
+        let mut spans: Vec<Span> = Vec::with_capacity(cols * 2 + 1);
+        if hex_shift(board, y) > 0 { spans.push(Span::raw(" ")); }
+        for dx in 0..cols {
+            let x = (dx + overlay.offset.0) % w;
+            if !board.playable(x, y) { spans.push(Span::raw("  ")); continue; }
             let c = board.cell(x, y).unwrap();
 
             let mut ch = if !board.alive() && c.is_mine() { '*' } else if c.revealed() {
                 if c.is_mine() { '*' } else if c.adjacent() == 0 { ' ' } else { char::from_digit(c.adjacent() as u32, 10).unwrap_or('?') }
-            } else if c.flagged() { 'F' } else { '·' };
+            } else if c.flagged() { 'F' } else if c.questioned() { '?' } else { '·' };
 
             // Color by state
             let mut style = if !board.alive() && c.is_mine() { Style::default().fg(Color::Red) }
                 else if c.flagged() { Style::default().fg(Color::Yellow) }
+                else if c.questioned() { Style::default().fg(Color::LightMagenta) }
                 else if c.revealed() { number_style(c.adjacent()) } else { Style::default().fg(Color::DarkGray) };
+            if let Some(p) = overlay.probs.as_ref().filter(|_| overlay.heatmap && board.alive()).and_then(|p| p.get(x, y)) {
+                style = heat_style(p);
+            }
+
+            if let Some(&(_, _, p)) = overlay.claims.iter().find(|&&(cx, cy, _)| (cx, cy) == (x, y)) {
+                style = Style::default().fg(Color::White).bg(PLAYER_COLORS[p]);
+            }
+            if overlay.hint == Some((x, y)) { style = style.bg(Color::Magenta).fg(Color::White); }
 
             // Highlight selected cell
             if cursor.0 == x && cursor.1 == y {
                 style = style.add_modifier(Modifier::REVERSED);
                 if ch == ' ' { ch = '·'; }
             }
+            if board.wrap() && y == h - 1 && dy + 1 < rows { style = style.add_modifier(Modifier::UNDERLINED); }
 
-            spans.push(Span::styled(format!("{} ", ch), style));
+            if board.wrap() && x == w - 1 && dx + 1 < cols {
+                spans.push(Span::styled(ch.to_string(), style));
+                spans.push(Span::styled("┊", Style::default().fg(Color::DarkGray)));
+            } else {
+                spans.push(Span::styled(format!("{} ", ch), style));
+            }
         }
         lines.push(Line::from(spans));
     }
 
-    let board_block = Block::default().borders(Borders::ALL).title("Board");
+    let board_block = if board.wrap() {
+        Block::default().borders(Borders::ALL).border_type(BorderType::Double).title("Board (wraps around)")
+    } else {
+        Block::default().borders(Borders::ALL).title("Board")
+    };
     let para = Paragraph::new(lines).block(board_block);
     f.render_widget(para, area);
 }
 
+// Background color for a mine probability: green is safe, red is a certain mine.
+fn heat_style(p: f64) -> Style {
+    let bg = if p <= 0.0 { Color::Green }
+        else if p < 0.15 { Color::LightGreen }
+        else if p < 0.35 { Color::LightYellow }
+        else if p < 0.65 { Color::Yellow }
+        else if p < 1.0 { Color::LightRed }
+        else { Color::Red };
+    Style::default().fg(Color::Black).bg(bg)
+}
+
 fn number_style(n: u8) -> Style {
     match n {
         0 => Style::default().fg(Color::Gray),
@@ -197,14 +665,121 @@ fn inner_area(area: Rect) -> Rect {
     Rect { x: area.x.saturating_add(1), y: area.y.saturating_add(1), width: area.width.saturating_sub(2), height: area.height.saturating_sub(2) }
 }
 
-fn pos_to_cell(mx: u16, my: u16, inner: Rect, cols: u16, rows: u16) -> Option<(u16, u16)> {
-    if mx < inner.x || my < inner.y { return None; }
-    let rel_x = mx - inner.x;
-    let rel_y = my - inner.y;
+// The cell under a mouse position, or `None` outside the part of the board in
+// view (the border, footer and minimap included).
+fn pos_to_cell(mx: u16, my: u16, inner: Rect, board: &Board, offset: (usize, usize)) -> Option<(u16, u16)> {
+    if mx < inner.x || my < inner.y || mx >= inner.right() || my >= inner.bottom() { return None; }
+    let (cols, rows) = visible(inner, board);
+    let rel_y = (my - inner.y) as usize;
+    if rel_y >= rows { return None; }
+    let cy = (rel_y + offset.1) % board.height();
+    let rel_x = (mx - inner.x).checked_sub(hex_shift(board, cy))?;
     let cell_w = 2u16; // must match centered_grid_area and rendering width
-    let cx = rel_x / cell_w;
-    let cy = rel_y / 1u16;
-    if cx < cols && cy < rows { Some((cx, cy)) } else { None }
+    let rel_x = (rel_x / cell_w) as usize;
+    if rel_x >= cols { return None; }
+    Some((((rel_x + offset.0) % board.width()) as u16, cy as u16))
+}
+
+// Step the cursor by (dx, dy), skipping mask holes and wrapping on a torus; stays
+// put when there is no playable cell in that direction.
+fn move_cursor(board: &Board, cursor: (usize, usize), dx: isize, dy: isize) -> (usize, usize) {
+    let (w, h) = (board.width() as isize, board.height() as isize);
+    let (mut x, mut y) = (cursor.0 as isize, cursor.1 as isize);
+    for _ in 0..w.max(h) {
+        (x, y) = (x + dx, y + dy);
+        if board.wrap() { (x, y) = (x.rem_euclid(w), y.rem_euclid(h)); }
+        if x < 0 || y < 0 || x >= w || y >= h { break; }
+        if board.playable(x as usize, y as usize) { return (x as usize, y as usize); }
+    }
+    cursor
+}
+
+// Jump the cursor by (dx, dy), stopping at the board edge (wrapping on a torus)
+// and moving on to the next playable cell if it lands in a hole.
+fn jump_cursor(board: &Board, cursor: (usize, usize), dx: isize, dy: isize) -> (usize, usize) {
+    let (w, h) = (board.width() as isize, board.height() as isize);
+    let (mut x, mut y) = (cursor.0 as isize + dx, cursor.1 as isize + dy);
+    if board.wrap() { (x, y) = (x.rem_euclid(w), y.rem_euclid(h)); } else { (x, y) = (x.clamp(0, w - 1), y.clamp(0, h - 1)); }
+    let target = (x as usize, y as usize);
+    if board.playable(target.0, target.1) { return target; }
+    let next = move_cursor(board, target, dx.signum(), dy.signum());
+    if board.playable(next.0, next.1) { next } else { cursor }
+}
+
+// Columns and rows of the board that fit inside the bordered area `inner`.
+fn visible(inner: Rect, board: &Board) -> (usize, usize) {
+    let cols = (inner.width.saturating_sub(hex_shift(board, 1)) as usize).div_ceil(2);
+    (cols.min(board.width()), (inner.height as usize).min(board.height()))
+}
+
+// Move the view by (dx, dy) cells: freely around a torus, otherwise clamped so
+// the view stays on the board.
+fn scroll(offset: &mut (usize, usize), dx: isize, dy: isize, view: (usize, usize), board: &Board) {
+    let axis = |off: usize, d: isize, view: usize, len: usize| -> usize {
+        if board.wrap() { return (off as isize + d).rem_euclid(len as isize) as usize; }
+        (off as isize + d).clamp(0, len.saturating_sub(view) as isize) as usize
+    };
+    *offset = (axis(offset.0, dx, view.0, board.width()), axis(offset.1, dy, view.1, board.height()));
+}
+
+// Scroll just far enough to bring the cursor into view.
+fn follow(offset: &mut (usize, usize), cursor: (usize, usize), view: (usize, usize), board: &Board) {
+    let axis = |off: usize, pos: usize, view: usize, len: usize| -> usize {
+        if view == 0 || view >= len { return if board.wrap() { off } else { 0 }; }
+        if !board.wrap() {
+            let off = off.min(len - view);
+            return if pos < off { pos } else if pos >= off + view { pos + 1 - view } else { off };
+        }
+        let rel = (pos + len - off) % len;
+        if rel < view { return off; }
+        // On a torus scroll whichever way is shorter.
+        let (forward, back) = (rel + 1 - view, len - rel);
+        if forward <= back { (off + forward) % len } else { (off + len - back) % len }
+    };
+    *offset = (axis(offset.0, cursor.0, view.0, board.width()), axis(offset.1, cursor.1, view.1, board.height()));
+}
+
+// Largest minimap, in characters; each character stands for a block of cells.
+const MAP_COLS: usize = 20;
+const MAP_ROWS: usize = 10;
+
+// Overview of a board too big for the terminal: the highlighted part is in view,
+// `+` is the cursor, and shading shows how much of each block is revealed.
+fn draw_minimap(f: &mut ratatui::Frame, parent: Rect, board: &Board, cursor: (usize, usize), offset: (usize, usize), view: (usize, usize)) {
+    // A terminal too small to show any cell has no view to map.
+    if view.0 == 0 || view.1 == 0 { return; }
+    let (w, h) = (board.width(), board.height());
+    let (mw, mh) = (w.min(MAP_COLS), h.min(MAP_ROWS));
+    let shown = |pos: usize, off: usize, view: usize, len: usize| (pos + len - off) % len < view;
+    let mut lines: Vec<Line> = (0..mh).map(|my| {
+        let (y0, y1) = (my * h / mh, (my + 1) * h / mh);
+        Line::from((0..mw).map(|mx| {
+            let (x0, x1) = (mx * w / mw, (mx + 1) * w / mw);
+            let block = (y0..y1).flat_map(|y| (x0..x1).map(move |x| (x, y))).filter(|&(x, y)| board.playable(x, y));
+            let (total, open) = block.fold((0, 0), |(t, o), (x, y)| (t + 1, o + board.cell(x, y).is_some_and(|c| c.revealed()) as usize));
+            let ch = if (x0..x1).contains(&cursor.0) && (y0..y1).contains(&cursor.1) { '+' }
+                else if total == 0 { ' ' } else if open == 0 { '·' } else if open * 2 < total { '░' } else { '▓' };
+            let style = if shown(x0, offset.0, view.0, w) && shown(y0, offset.1, view.1, h) { Style::default().fg(Color::Black).bg(Color::Cyan) }
+                else { Style::default().fg(Color::DarkGray) };
+            Span::styled(ch.to_string(), style)
+        }).collect::<Vec<_>>())
+    }).collect();
+    let span = |off: usize, view: usize, len: usize| format!("{}-{}/{}", off + 1, (off + view - 1) % len + 1, len);
+    lines.push(Line::from(format!("x {}", span(offset.0, view.0, w))));
+    lines.push(Line::from(format!("y {}", span(offset.1, view.1, h))));
+    let rect = Rect { height: (lines.len() as u16 + 2).min(parent.height), ..parent };
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Map")), rect);
+}
+
+fn first_playable(board: &Board) -> (usize, usize) {
+    (0..board.height()).flat_map(|y| (0..board.width()).map(move |x| (x, y)))
+        .find(|&(x, y)| board.playable(x, y))
+        .unwrap_or((0, 0))
+}
+
+// Columns that row `y` is shifted right by: one on odd rows of hex boards.
+fn hex_shift(board: &Board, y: usize) -> u16 {
+    (board.topology() == Topology::Hex && y % 2 == 1) as u16
 }
 
 struct TermGuard;
@@ -0,0 +1,109 @@
+//! The Flags variant in the terminal: two players share one board and keyboard,
+//! taking turns to hunt for mines.
+
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use super::{centered_grid_area, draw_board, first_playable, follow, inner_area, move_cursor, pos_to_cell, setup_terminal, visible, NewGame, Overlay, TuiOptions, PLAYER_COLORS};
+use crate::engine::flags::FlagsGame;
+use crate::engine::{Error, RevealResult};
+
+/// Run a Flags match: arrows/HJKL move, Enter/Space or a left click reveals for
+/// the player to move, `n` starts a new match, `q` quits. Only the board settings
+/// of `opts` are used.
+pub fn run_flags(opts: TuiOptions) -> Result<(), Error> {
+    let TuiOptions { width, height, mines, seed, no_guess, topology, wrap, question_marks, mask, .. } = opts;
+    let mut settings = NewGame { width, height, mines, seed, no_guess, topology, wrap, question_marks, mask };
+    let mut game = FlagsGame::new(settings.board()?);
+    let (mut terminal, _guard) = setup_terminal()?;
+    let mut cursor = first_playable(game.board());
+    let mut message: Option<String> = None;
+    let mut inner = Rect::default();
+    let mut offset = (0, 0);
+    let res = loop {
+        follow(&mut offset, cursor, visible(inner, game.board()), game.board());
+        terminal.draw(|f| { inner = draw(f, &game, cursor, offset, message.as_deref()); })?;
+        if !event::poll(Duration::from_millis(250))? { continue; }
+        let target = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break Ok(()),
+                KeyCode::Char('n') => {
+                    settings.seed = 0;
+                    game = FlagsGame::new(settings.board()?);
+                    cursor = first_playable(game.board());
+                    offset = (0, 0);
+                    message = None;
+                    continue;
+                }
+                KeyCode::Char('h') | KeyCode::Left => { cursor = move_cursor(game.board(), cursor, -1, 0); continue; }
+                KeyCode::Char('l') | KeyCode::Right => { cursor = move_cursor(game.board(), cursor, 1, 0); continue; }
+                KeyCode::Char('k') | KeyCode::Up => { cursor = move_cursor(game.board(), cursor, 0, -1); continue; }
+                KeyCode::Char('j') | KeyCode::Down => { cursor = move_cursor(game.board(), cursor, 0, 1); continue; }
+                KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('r') => cursor,
+                _ => continue,
+            },
+            Event::Mouse(m) if m.kind == MouseEventKind::Down(MouseButton::Left) => {
+                let Some((x, y)) = pos_to_cell(m.column, m.row, inner, game.board(), offset) else { continue };
+                cursor = (x as usize, y as usize);
+                cursor
+            }
+            _ => continue,
+        };
+        let player = game.turn();
+        message = match game.reveal(target.0, target.1) {
+            Ok(RevealResult::HitMine) => Some(format!("Player {} claims a mine and goes again!", player + 1)),
+            Ok(_) => None,
+            Err(e) => Some(format!("Cannot reveal: {}", e)),
+        };
+    };
+    terminal.show_cursor()?;
+    res
+}
+
+// Header with the turn or the result, the shared board and a score line; returns
+// the board's inner area for mouse hits.
+fn draw(f: &mut ratatui::Frame, game: &FlagsGame, cursor: (usize, usize), offset: (usize, usize), message: Option<&str>) -> Rect {
+    let root = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(5), Constraint::Length(3)])
+        .split(f.size());
+    let player_style = |p: usize| Style::default().fg(PLAYER_COLORS[p]).add_modifier(Modifier::BOLD);
+    let status = if game.finished() {
+        match game.winner() {
+            Some(p) => Line::from(vec![Span::styled(format!("Player {} wins!", p + 1), player_style(p)), Span::raw(" n new match • q quit")]),
+            None => Line::from("All mines claimed — a tie! n new match • q quit"),
+        }
+    } else {
+        let p = game.turn();
+        let mut spans = vec![Span::styled(format!("Player {} to move", p + 1), player_style(p))];
+        if let Some(msg) = message { spans.push(Span::raw(format!(" • {}", msg))); }
+        spans.push(Span::raw(" • Arrows/HJKL move • Enter/Space/click reveal • n new • q quit"));
+        Line::from(spans)
+    };
+    let header = Paragraph::new(status)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Minesweeper Flags"));
+    f.render_widget(header, root[0]);
+
+    let area = centered_grid_area(root[1], game.board());
+    let overlay = Overlay { claims: game.claims().to_vec(), offset, ..Overlay::default() };
+    draw_board(f, game.board(), area, cursor, &overlay);
+
+    let scores = game.scores();
+    let left = game.board().mines() - game.claims().len();
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled(format!("Player 1: {}", scores[0]), player_style(0)),
+        Span::raw(format!("   💣 {} unclaimed, {} to win   ", left, game.target())),
+        Span::styled(format!("Player 2: {}", scores[1]), player_style(1)),
+    ]))
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, root[2]);
+    inner_area(area)
+}
@@ -0,0 +1,131 @@
+//! The race in the terminal: two players on one keyboard, side by side on
+//! identical boards (see `engine::race`).
+
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use super::{centered_grid_area, draw_board, follow, inner_area, move_cursor, setup_terminal, visible, NewGame, Overlay, TuiOptions};
+use crate::engine::race::{Outcome, Race};
+use crate::engine::{Action, Error};
+
+// A key press: move the cursor or play an action at it.
+enum Input {
+    Move(isize, isize),
+    Play(fn(usize, usize) -> Action),
+}
+
+const NAMES: [&str; 2] = ["Player 1 (WASD)", "Player 2 (arrows)"];
+
+/// Run a race in the terminal. Player 1 moves with WASD, reveals with `e`, flags
+/// with `q` and chords with `r`; player 2 uses the arrows, Enter, `.` and `/`.
+/// `n` starts a rematch on a fresh board and Esc quits. Only the board settings
+/// of `opts` are used.
+pub fn run_race(opts: TuiOptions) -> Result<(), Error> {
+    let TuiOptions { width, height, mines, seed, no_guess, topology, wrap, question_marks, mask, .. } = opts;
+    let mut settings = NewGame { width, height, mines, seed, no_guess, topology, wrap, question_marks, mask };
+    let mut race = Race::new(settings.board()?);
+    let (mut terminal, _guard) = setup_terminal()?;
+    let mut cursors = [race.opening(); 2];
+    let mut offsets = [(0, 0); 2];
+    let mut inners = [Rect::default(); 2];
+    let res = loop {
+        for p in 0..2 { follow(&mut offsets[p], cursors[p], visible(inners[p], race.board(p)), race.board(p)); }
+        terminal.draw(|f| { inners = draw(f, &race, cursors, offsets); })?;
+        if !event::poll(Duration::from_millis(100))? { continue; }
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press { continue; }
+        let (player, input) = match key.code {
+            KeyCode::Esc => break Ok(()),
+            KeyCode::Char('n') | KeyCode::Char('N') => {
+                // Rematches get a fresh random layout.
+                settings.seed = 0;
+                race = Race::new(settings.board()?);
+                cursors = [race.opening(); 2];
+                offsets = [(0, 0); 2];
+                continue;
+            }
+            KeyCode::Char(c) => match c.to_ascii_lowercase() {
+                'w' => (0, Input::Move(0, -1)),
+                'a' => (0, Input::Move(-1, 0)),
+                's' => (0, Input::Move(0, 1)),
+                'd' => (0, Input::Move(1, 0)),
+                'e' => (0, Input::Play(Action::Reveal)),
+                'q' => (0, Input::Play(Action::ToggleFlag)),
+                'r' => (0, Input::Play(Action::Chord)),
+                '.' => (1, Input::Play(Action::ToggleFlag)),
+                '/' => (1, Input::Play(Action::Chord)),
+                _ => continue,
+            },
+            KeyCode::Up => (1, Input::Move(0, -1)),
+            KeyCode::Left => (1, Input::Move(-1, 0)),
+            KeyCode::Down => (1, Input::Move(0, 1)),
+            KeyCode::Right => (1, Input::Move(1, 0)),
+            KeyCode::Enter => (1, Input::Play(Action::Reveal)),
+            _ => continue,
+        };
+        let (x, y) = cursors[player];
+        match input {
+            Input::Move(dx, dy) => cursors[player] = move_cursor(race.board(player), (x, y), dx, dy),
+            Input::Play(action) => { let _ = race.play(player, action(x, y)); }
+        }
+    };
+    terminal.show_cursor()?;
+    res
+}
+
+// Returns each player's inner board area.
+fn draw(f: &mut ratatui::Frame, race: &Race, cursors: [(usize, usize); 2], offsets: [(usize, usize); 2]) -> [Rect; 2] {
+    let root = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(5)])
+        .split(f.size());
+    let secs = race.elapsed().as_secs_f64();
+    let status = match race.outcome() {
+        None => format!("⏱ {:.1}s • P1: WASD move, e reveal, q flag, r chord • P2: arrows move, Enter reveal, . flag, / chord • Esc quit", secs),
+        Some(Outcome { winner: None, .. }) => "The opening cleared both boards — a draw! n rematch • Esc quit".to_string(),
+        Some(Outcome { winner: Some(p), by_mine, time }) => {
+            let how = if by_mine { format!("{} hit a mine", NAMES[1 - p]) } else { format!("cleared the board in {:.1}s", time.as_secs_f64()) };
+            format!("🏆 {} wins! ({}) • n rematch • Esc quit", NAMES[p], how)
+        }
+    };
+    let header = Paragraph::new(status)
+        .style(Style::default().fg(Color::Cyan))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(format!("Minesweeper race • seed {}", race.board(0).seed())));
+    f.render_widget(header, root[0]);
+
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(root[1]);
+    [0, 1].map(|p| draw_player(f, race, p, cursors[p], offsets[p], halves[p]))
+}
+
+// One player's column: their board and a status line underneath.
+fn draw_player(f: &mut ratatui::Frame, race: &Race, player: usize, cursor: (usize, usize), offset: (usize, usize), area: Rect) -> Rect {
+    let board = race.board(player);
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+    let grid = centered_grid_area(parts[0], board);
+    draw_board(f, board, grid, cursor, &Overlay { offset, ..Overlay::default() });
+
+    let state = match race.outcome() {
+        Some(Outcome { winner: Some(p), .. }) if p == player => "🏆 winner",
+        _ if !board.alive() => "😵 boom",
+        Some(_) => "🏁 finished",
+        None => "🙂 racing",
+    };
+    let left = board.remaining_safe();
+    let footer = Paragraph::new(format!("💣 {:03}   {}   {} cells to go", board.mines_left(), state, left))
+        .style(Style::default().fg(if race.outcome().and_then(|o| o.winner) == Some(player) { Color::Green } else { Color::DarkGray }))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(NAMES[player]));
+    f.render_widget(footer, parts[1]);
+    inner_area(grid)
+}
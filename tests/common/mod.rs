@@ -0,0 +1,26 @@
+//! Helpers shared by the integration tests; each test crate uses only some.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use minesweeper::engine::{Board, Cell};
+
+/// `file` inside a fresh per-test, per-process temp directory.
+pub fn temp_file(name: &str, file: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("minesweeper-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir.join(file)
+}
+
+/// Every cell coordinate of a board, row by row.
+pub fn cells(b: &Board) -> Vec<(usize, usize)> {
+    (0..b.height()).flat_map(|y| (0..b.width()).map(move |x| (x, y))).collect()
+}
+
+/// What a player sees of a board, without the timer: every cell, plus whether
+/// the game is alive, won and how many safe cells remain.
+pub fn position(b: &Board) -> (Vec<Cell>, bool, bool, usize) {
+    let cells = cells(b).into_iter().map(|(x, y)| b.cell(x, y).unwrap().clone()).collect();
+    (cells, b.alive(), b.won(), b.remaining_safe())
+}
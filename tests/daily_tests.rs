@@ -0,0 +1,55 @@
+mod common;
+
+use std::fs;
+
+use minesweeper::daily::{Attempt, Daily, DailyLog};
+use common::temp_file;
+
+// 2026-10-17 12:00 UTC
+const DAY: u64 = 1_792_238_400;
+
+#[test]
+fn every_player_gets_the_same_board_for_a_day() {
+    let daily = Daily::on(DAY);
+    assert_eq!(daily.date, "2026-10-17");
+    assert_eq!(daily, Daily::on(DAY - DAY % 86_400), "the whole UTC day shares one board");
+    assert_eq!(daily.spec.to_string(), "xs64:10677320958460148310:16x16:40:6,4");
+    assert_ne!(Daily::on(DAY + 86_400).spec, daily.spec);
+    let board = daily.board().unwrap();
+    assert!(board.cell(5, 3).unwrap().revealed());
+    assert_eq!(board.first_click(), Some((5, 3)));
+    assert_eq!(board.stats().clicks.total(), 0, "the automatic first click is not the player's");
+}
+
+#[test]
+fn the_latest_attempt_of_a_day_counts() {
+    let path = temp_file("daily-log", "daily.txt");
+    let daily = Daily::on(DAY);
+    let mut board = daily.board().unwrap();
+    let mut log = DailyLog::load(&path);
+    log.record(Attempt::from_board(&daily.date, &board)).unwrap();
+    let started = DailyLog::load(&path).get(&daily.date).cloned().unwrap();
+    assert!(!started.finished && !started.won);
+    let mine = (0..16).flat_map(|y| (0..16).map(move |x| (x, y))).find(|&(x, y)| board.cell(x, y).unwrap().is_mine()).unwrap();
+    board.reveal(mine.0, mine.1);
+    log.record(Attempt::from_board(&daily.date, &board)).unwrap();
+    let log = DailyLog::load(&path);
+    let last = log.get(&daily.date).unwrap();
+    assert!(last.finished && !last.won);
+    assert_eq!(last.clicks, 1);
+    assert!(log.get("2026-10-18").is_none());
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn the_share_text_hides_unflagged_mines() {
+    let daily = Daily::on(DAY);
+    let mut board = daily.board().unwrap();
+    board.toggle_flag(0, 0);
+    let share = Attempt::from_board(&daily.date, &board).share();
+    let lines: Vec<&str> = share.lines().collect();
+    assert_eq!(lines[0], "Minesweeper Daily 2026-10-17 🏳 gave up at 0/49 3BV");
+    assert_eq!(lines.len(), 2 + 16);
+    assert!(lines[2].starts_with('🚩'));
+    assert!(lines[2..].iter().all(|row| row.chars().count() == 16 && row.chars().all(|c| "🟩🚩⬛".contains(c))));
+}
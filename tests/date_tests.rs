@@ -0,0 +1,8 @@
+use minesweeper::date::format_ymd;
+
+#[test]
+fn dates_format_as_utc_days() {
+    assert_eq!(format_ymd(0), "1970-01-01");
+    assert_eq!(format_ymd(951_782_400), "2000-02-29");
+    assert_eq!(format_ymd(1_792_195_199), "2026-10-16");
+}
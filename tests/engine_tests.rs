@@ -1,4 +1,7 @@
-use minesweeper::engine::Board;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use minesweeper::engine::{Action, Board, Clock, Error, RevealResult, Topology};
 
 fn neighbors(w: usize, h: usize, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
     let x = x as isize; let y = y as isize; let w = w as isize; let h = h as isize;
@@ -42,3 +45,220 @@ fn adjacency_matches_neighbor_mines() {
     assert_eq!(mine_count, b.mines());
 }
 
+
+fn find_number(b: &Board) -> Option<(usize, usize)> {
+    for y in 0..b.height() {
+        for x in 0..b.width() {
+            let c = b.cell(x, y).unwrap();
+            if !c.revealed() || c.adjacent() == 0 { continue; }
+            let hidden_safe = neighbors(b.width(), b.height(), x, y)
+                .any(|(nx, ny)| { let n = b.cell(nx, ny).unwrap(); !n.revealed() && !n.is_mine() });
+            if hidden_safe { return Some((x, y)); }
+        }
+    }
+    None
+}
+
+#[test]
+fn chord_reveals_unflagged_neighbors() {
+    let mut b = Board::new(9, 9, 10, 12345).expect("board");
+    let _ = b.reveal(0, 0);
+    let (x, y) = find_number(&b).expect("a number with covered safe neighbors");
+    assert_eq!(b.chord(x, y), RevealResult::NoOp, "chord without flags must be ignored");
+    for (nx, ny) in neighbors(b.width(), b.height(), x, y) {
+        if b.cell(nx, ny).unwrap().is_mine() { assert!(b.toggle_flag(nx, ny)); }
+    }
+    assert_eq!(b.chord(x, y), RevealResult::RevealedSafe);
+    for (nx, ny) in neighbors(b.width(), b.height(), x, y) {
+        let c = b.cell(nx, ny).unwrap();
+        assert!(c.revealed() || c.flagged(), "({},{}) left covered", nx, ny);
+    }
+}
+
+#[test]
+fn chord_with_wrong_flag_hits_mine() {
+    let mut b = Board::new(9, 9, 10, 12345).expect("board");
+    let _ = b.reveal(0, 0);
+    let (x, y) = find_number(&b).expect("a number with covered safe neighbors");
+    let adjacent = b.cell(x, y).unwrap().adjacent() as usize;
+    let wrong: Vec<(usize, usize)> = neighbors(b.width(), b.height(), x, y)
+        .filter(|&(nx, ny)| { let n = b.cell(nx, ny).unwrap(); !n.revealed() && !n.is_mine() })
+        .take(adjacent)
+        .collect();
+    assert_eq!(wrong.len(), adjacent, "need enough covered safe cells to misflag");
+    for &(nx, ny) in &wrong { b.toggle_flag(nx, ny); }
+    assert_eq!(b.chord(x, y), RevealResult::HitMine);
+    assert!(!b.alive());
+}
+
+#[test]
+fn undo_and_redo_restore_moves() {
+    let mut b = Board::new(9, 9, 10, 12345).expect("board");
+    let _ = b.reveal(0, 0);
+    let revealed = b.remaining_safe();
+    assert!(b.toggle_flag(8, 8));
+
+    let step = b.undo().expect("undo flag");
+    assert_eq!(step.action, Action::ToggleFlag(8, 8));
+    assert_eq!(step.cells, vec![(8, 8)]);
+    assert!(!b.cell(8, 8).unwrap().flagged());
+
+    let step = b.undo().expect("undo reveal");
+    assert_eq!(step.action, Action::Reveal(0, 0));
+    assert!(!b.cell(0, 0).unwrap().revealed());
+    assert!(b.undo().is_none());
+
+    b.redo().expect("redo reveal");
+    assert!(b.cell(0, 0).unwrap().revealed());
+    assert_eq!(b.remaining_safe(), revealed);
+    b.redo().expect("redo flag");
+    assert!(b.cell(8, 8).unwrap().flagged());
+    assert!(b.redo().is_none());
+}
+
+#[test]
+fn undo_recovers_from_hitting_a_mine() {
+    let mut b = Board::new(9, 9, 10, 12345).expect("board");
+    let _ = b.reveal(0, 0);
+    let mine = (0..81).map(|i| (i % 9, i / 9)).find(|&(x, y)| b.cell(x, y).unwrap().is_mine()).unwrap();
+    assert_eq!(b.reveal(mine.0, mine.1), RevealResult::HitMine);
+    assert!(!b.alive());
+    b.undo().expect("undo mine hit");
+    assert!(b.alive());
+    assert_eq!(b.reveal(mine.0, mine.1), RevealResult::HitMine);
+    assert!(b.redo().is_none(), "a new move clears the redo stack");
+}
+
+struct ManualClock(Mutex<Duration>);
+impl Clock for ManualClock {
+    fn now(&self) -> Duration { *self.0.lock().unwrap() }
+}
+impl ManualClock {
+    fn advance(&self, secs: u64) { *self.0.lock().unwrap() += Duration::from_secs(secs); }
+}
+
+#[test]
+fn timer_runs_from_first_reveal_until_game_ends() {
+    let clock = Arc::new(ManualClock(Mutex::new(Duration::from_secs(100))));
+    let mut b = Board::new(9, 9, 10, 12345).expect("board").with_clock(clock.clone());
+    clock.advance(5);
+    assert_eq!(b.elapsed(), Duration::ZERO, "timer must wait for the first reveal");
+    let _ = b.reveal(0, 0);
+    clock.advance(7);
+    assert_eq!(b.elapsed(), Duration::from_secs(7));
+
+    let mine = (0..81).map(|i| (i % 9, i / 9)).find(|&(x, y)| b.cell(x, y).unwrap().is_mine()).unwrap();
+    let _ = b.reveal(mine.0, mine.1);
+    clock.advance(30);
+    assert_eq!(b.elapsed(), Duration::from_secs(7), "timer stops on loss");
+
+    b.undo();
+    clock.advance(2);
+    assert_eq!(b.elapsed(), Duration::from_secs(9), "undo resumes the timer");
+}
+
+#[test]
+fn mine_counter_tracks_flags() {
+    let mut b = Board::new(9, 9, 10, 1).expect("board");
+    assert_eq!(b.mines_left(), 10);
+    b.toggle_flag(0, 0);
+    b.toggle_flag(1, 0);
+    assert_eq!((b.flags(), b.mines_left()), (2, 8));
+}
+
+#[test]
+fn hex_cells_have_six_neighbors_in_offset_rows() {
+    let b = Board::new(5, 5, 3, 1).expect("board").with_topology(Topology::Hex).unwrap();
+    let mut even: Vec<_> = b.neighbors(2, 2).collect();
+    even.sort();
+    assert_eq!(even, vec![(1, 1), (1, 2), (1, 3), (2, 1), (2, 3), (3, 2)]);
+    let mut odd: Vec<_> = b.neighbors(2, 1).collect();
+    odd.sort();
+    assert_eq!(odd, vec![(1, 1), (2, 0), (2, 2), (3, 0), (3, 1), (3, 2)]);
+    assert_eq!(b.neighbors(0, 0).count(), 2);
+    assert_eq!(b.neighbors(4, 1).count(), 3);
+}
+
+#[test]
+fn hex_board_counts_only_hex_neighbors() {
+    let mut b = Board::new(10, 8, 20, 4242).expect("board").with_topology(Topology::Hex).unwrap();
+    let _ = b.reveal(5, 4);
+    for y in 0..b.height() {
+        for x in 0..b.width() {
+            let c = b.cell(x, y).unwrap();
+            if c.is_mine() { continue; }
+            let adj = b.neighbors(x, y).filter(|&(nx, ny)| b.cell(nx, ny).unwrap().is_mine()).count();
+            assert_eq!(c.adjacent() as usize, adj, "adjacency mismatch at ({},{})", x, y);
+            assert!(c.adjacent() <= 6);
+        }
+    }
+    let rows: Vec<String> = b.render(false, true).lines().skip(2).map(String::from).collect();
+    assert!(rows[1].starts_with(" 2 |   "), "odd rows are offset: {:?}", rows[1]);
+    assert!(!rows[0].starts_with(" 1 |  "), "even rows are not: {:?}", rows[0]);
+}
+
+#[test]
+fn wrapped_board_gives_every_cell_eight_neighbors() {
+    let b = Board::new(6, 5, 4, 3).expect("board").with_wrap(true).unwrap();
+    let mut corner: Vec<_> = b.neighbors(0, 0).collect();
+    corner.sort();
+    assert_eq!(corner, vec![(0, 1), (0, 4), (1, 0), (1, 1), (1, 4), (5, 0), (5, 1), (5, 4)]);
+    for y in 0..5 {
+        for x in 0..6 { assert_eq!(b.neighbors(x, y).count(), 8); }
+    }
+    // Too narrow to wrap without repeats: each neighbor is listed once.
+    let thin = Board::new(2, 4, 1, 3).expect("board").with_wrap(true).unwrap();
+    assert_eq!(thin.neighbors(0, 0).count(), 5);
+}
+
+#[test]
+fn wrapped_hex_boards_need_an_even_height() {
+    let odd = || Board::new(9, 9, 10, 1).expect("board");
+    assert!(matches!(odd().with_topology(Topology::Hex).unwrap().with_wrap(true), Err(Error::HexWrapOddHeight { height: 9 })));
+    assert!(matches!(odd().with_wrap(true).unwrap().with_topology(Topology::Hex), Err(Error::HexWrapOddHeight { .. })));
+    assert!(Board::new(9, 8, 10, 1).expect("board").with_topology(Topology::Hex).unwrap().with_wrap(true).is_ok());
+}
+
+#[test]
+fn wrapped_no_guess_opening_spans_the_seam() {
+    let mut b = Board::new(10, 10, 30, 2024).expect("board").with_wrap(true).unwrap().with_no_guess(true);
+    assert_eq!(b.reveal(0, 0), RevealResult::RevealedSafe);
+    for (x, y) in b.neighbors(0, 0).collect::<Vec<_>>() {
+        assert!(!b.cell(x, y).unwrap().is_mine(), "mine next to the first click at ({},{})", x, y);
+        assert!(b.cell(x, y).unwrap().revealed(), "flood fill crosses the seam at ({},{})", x, y);
+    }
+    for y in 0..10 {
+        for x in 0..10 {
+            let c = b.cell(x, y).unwrap();
+            if c.is_mine() { continue; }
+            let adj = b.neighbors(x, y).filter(|&(nx, ny)| b.cell(nx, ny).unwrap().is_mine()).count();
+            assert_eq!(c.adjacent() as usize, adj, "adjacency mismatch at ({},{})", x, y);
+        }
+    }
+}
+
+#[test]
+fn question_marks_cycle_and_do_not_block_reveals() {
+    let mut b = Board::new(9, 9, 10, 12345).expect("board").with_question_marks(true);
+    let _ = b.reveal(0, 0);
+    let covered = (0..9).flat_map(|y| (0..9).map(move |x| (x, y)))
+        .find(|&(x, y)| { let c = b.cell(x, y).unwrap(); !c.revealed() && !c.is_mine() }).unwrap();
+    let (x, y) = covered;
+    assert!(b.try_toggle_flag(x, y).unwrap());
+    assert!(!b.try_toggle_flag(x, y).unwrap());
+    assert!(b.cell(x, y).unwrap().questioned());
+    assert_eq!(b.flags(), 0, "a question mark is not a flag");
+    assert!(b.render(false, true).contains('?'));
+    assert_eq!(b.reveal(x, y), RevealResult::RevealedSafe);
+    assert!(!b.cell(x, y).unwrap().questioned());
+    let _ = b.undo();
+    assert!(b.cell(x, y).unwrap().questioned(), "undo restores the mark");
+    assert!(!b.try_toggle_flag(x, y).unwrap());
+    assert!(!b.cell(x, y).unwrap().questioned() && !b.cell(x, y).unwrap().flagged());
+
+    // Flags only: the cycle skips the question mark.
+    let mut plain = Board::new(9, 9, 10, 12345).expect("board");
+    assert!(plain.toggle_flag(4, 4));
+    let _ = plain.toggle_flag(4, 4);
+    assert!(!plain.cell(4, 4).unwrap().questioned() && !plain.cell(4, 4).unwrap().flagged());
+}
@@ -0,0 +1,54 @@
+use std::error::Error as _;
+
+use minesweeper::engine::mask::Mask;
+use minesweeper::engine::replay::{Replay, ReplayError};
+use minesweeper::engine::save;
+use minesweeper::engine::{Board, Error, RevealResult};
+
+#[test]
+fn board_parameters_are_checked() {
+    assert!(matches!(Board::new(0, 5, 1, 1), Err(Error::InvalidDimensions { width: 0, height: 5 })));
+    assert!(matches!(Board::new(3, 3, 9, 1), Err(Error::TooManyMines { mines: 9, cells: 9 })));
+    let mask = Mask::parse("###\n# #\n").unwrap();
+    assert!(matches!(Board::from_mask(mask, 5, 1), Err(Error::TooManyMines { mines: 5, cells: 5 })));
+    let msg = Board::new(3, 3, 10, 1).err().unwrap().to_string();
+    assert!(msg.contains("10 mines") && msg.contains("9 cells"), "{}", msg);
+}
+
+#[test]
+fn rejected_moves_say_why() {
+    let mut b = Board::new(9, 9, 10, 12345).expect("board");
+    assert!(matches!(b.try_reveal(9, 0), Err(Error::OutOfBounds { x: 9, y: 0 })));
+    assert!(b.try_toggle_flag(8, 8).unwrap(), "now flagged");
+    assert!(matches!(b.try_reveal(8, 8), Err(Error::Flagged { x: 8, y: 8 })));
+    assert!(!b.try_toggle_flag(8, 8).unwrap(), "flag removed");
+    assert_eq!(b.try_reveal(0, 0).unwrap(), RevealResult::RevealedSafe);
+    assert!(matches!(b.try_reveal(0, 0), Err(Error::AlreadyRevealed { .. })));
+    assert!(matches!(b.try_toggle_flag(0, 0), Err(Error::AlreadyRevealed { .. })));
+    let covered = (0..9).flat_map(|y| (0..9).map(move |x| (x, y))).find(|&(x, y)| !b.cell(x, y).unwrap().revealed()).unwrap();
+    assert!(matches!(b.try_chord(covered.0, covered.1), Err(Error::NotANumber { .. })));
+    let number = (0..9).flat_map(|y| (0..9).map(move |x| (x, y)))
+        .find(|&(x, y)| { let c = b.cell(x, y).unwrap(); c.revealed() && c.adjacent() > 0 }).unwrap();
+    match b.try_chord(number.0, number.1) {
+        Err(Error::FlagMismatch { flags: 0, needed, .. }) => assert!(needed > 0),
+        other => panic!("unexpected chord result {:?}", other),
+    }
+    // The lenient wrappers still report plain NoOp / false.
+    assert_eq!(b.reveal(0, 0), RevealResult::NoOp);
+    assert!(!b.toggle_flag(20, 20));
+    assert_eq!(Error::OutOfBounds { x: 9, y: 0 }.to_string(), "(10,1) is outside the board");
+}
+
+#[test]
+fn file_errors_keep_their_cause() {
+    let err: Error = save::decode("not a save").err().expect("rejected").into();
+    assert!(matches!(err, Error::Save(_)));
+    assert!(err.source().is_some());
+    let text = "minesweeper-replay 1\nsize 3 3\nmines 9\nseed 1\nevents\n";
+    let replay = Replay::decode(text).expect("decode");
+    match replay.board() {
+        Err(ReplayError::InvalidBoard(inner)) => assert!(matches!(*inner, Error::TooManyMines { .. })),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("9 mines on 9 cells accepted"),
+    }
+}
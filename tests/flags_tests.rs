@@ -0,0 +1,76 @@
+mod common;
+
+use std::time::Duration;
+
+use minesweeper::engine::flags::FlagsGame;
+use minesweeper::engine::replay::{Replay, Step};
+use minesweeper::engine::save;
+use minesweeper::engine::solver::{probabilities, solve};
+use minesweeper::engine::{Action, Board, Error, RevealResult};
+use common::cells;
+
+fn opened(seed: u64) -> FlagsGame {
+    let mut game = FlagsGame::new(Board::new(9, 9, 11, seed).unwrap());
+    assert_eq!(game.reveal(4, 4).unwrap(), RevealResult::RevealedSafe);
+    game
+}
+
+#[test]
+fn mines_are_claimed_and_keep_the_turn() {
+    let mut game = opened(42);
+    assert_eq!(game.turn(), 1, "a safe reveal passes the turn");
+    let mine = cells(game.board()).into_iter().find(|&(x, y)| game.board().cell(x, y).unwrap().is_mine()).unwrap();
+    assert_eq!(game.reveal(mine.0, mine.1).unwrap(), RevealResult::HitMine);
+    assert_eq!(game.turn(), 1);
+    assert_eq!(game.scores(), [0, 1]);
+    assert_eq!(game.claims(), &[(mine.0, mine.1, 1)]);
+    assert!(game.board().alive() && game.board().cell(mine.0, mine.1).unwrap().revealed());
+    assert!(matches!(game.reveal(mine.0, mine.1), Err(Error::AlreadyRevealed { .. })));
+}
+
+#[test]
+fn more_than_half_the_mines_wins() {
+    let mut game = opened(7);
+    assert_eq!(game.target(), 6);
+    let mines: Vec<_> = cells(game.board()).into_iter().filter(|&(x, y)| game.board().cell(x, y).unwrap().is_mine()).collect();
+    for &(x, y) in &mines[..5] { game.reveal(x, y).unwrap(); }
+    assert!(!game.finished());
+    game.reveal(mines[5].0, mines[5].1).unwrap();
+    assert_eq!(game.winner(), Some(1));
+    assert!(game.finished());
+    assert!(matches!(game.reveal(mines[6].0, mines[6].1), Err(Error::GameOver)));
+}
+
+#[test]
+fn clearing_every_safe_cell_does_not_end_a_hunt() {
+    let mut board = Board::new(4, 4, 2, 3).unwrap().with_mine_hunt(true);
+    let _ = board.reveal(0, 0);
+    for (x, y) in cells(&board) {
+        if !board.cell(x, y).unwrap().is_mine() { let _ = board.reveal(x, y); }
+    }
+    assert_eq!(board.remaining_safe(), 0);
+    assert!(!board.won() && board.alive());
+    let mine = cells(&board).into_iter().find(|&(x, y)| board.cell(x, y).unwrap().is_mine()).unwrap();
+    assert_eq!(board.try_reveal(mine.0, mine.1).unwrap(), RevealResult::HitMine);
+}
+
+#[test]
+fn hunt_boards_survive_saves_replays_and_the_solver() {
+    let mut game = opened(42);
+    let mine = cells(game.board()).into_iter().find(|&(x, y)| game.board().cell(x, y).unwrap().is_mine()).unwrap();
+    game.reveal(mine.0, mine.1).unwrap();
+    let board = game.board();
+
+    let loaded = save::decode(&save::encode(board)).expect("hunt save loads");
+    assert!(loaded.mine_hunt() && loaded.alive() && loaded.cell(mine.0, mine.1).unwrap().revealed());
+    let mut replay = Replay::new(board);
+    replay.push(Duration::ZERO, Step::Move(Action::Reveal(4, 4)));
+    assert!(Replay::decode(&replay.encode()).unwrap().board().unwrap().mine_hunt());
+
+    // The claimed mine counts as a known mine, never as a safe cell.
+    let d = solve(board);
+    assert!(!d.safe.contains(&mine) && !d.mines.contains(&mine));
+    assert!(d.safe.iter().all(|&(x, y)| !board.cell(x, y).unwrap().is_mine()));
+    assert!(d.mines.iter().all(|&(x, y)| board.cell(x, y).unwrap().is_mine()));
+    assert_eq!(probabilities(board).get(mine.0, mine.1), None);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::fs;
+use std::time::Duration;
+
+use minesweeper::engine::{Board, Topology};
+use minesweeper::scores::Category;
+use minesweeper::stats::{self, GameRecord, StatsLog};
+use common::temp_file;
+
+fn game(player: &str, width: usize, won: bool, secs: u64) -> GameRecord {
+    GameRecord {
+        player: player.into(),
+        date: 0,
+        category: Category { width, height: 9, mines: 10, no_guess: false, topology: Topology::Square, wrap: false, mask: None },
+        won,
+        time: Duration::from_secs(secs),
+        bbbv: 20,
+        solved_bbbv: if won { 20 } else { 5 },
+        clicks: 25,
+    }
+}
+
+#[test]
+fn summaries_aggregate_rates_streaks_and_times() {
+    let path = temp_file("lifetime-summary", "stats.txt");
+    let mut log = StatsLog::load(&path);
+    for (won, secs) in [(true, 8), (true, 20), (false, 3), (true, 40), (true, 10), (false, 1)] {
+        log.record(game("ann", 9, won, secs)).unwrap();
+    }
+    log.record(game("ann", 12, true, 5)).unwrap();
+    log.record(game("bob", 9, false, 2)).unwrap();
+    let s = log.summary("ann");
+    assert_eq!((s.played, s.won, s.current_streak, s.best_streak), (7, 5, 1, 2));
+    assert_eq!(s.average_time_ms, Some(16_600));
+    assert_eq!(s.best_bbbv_per_sec, Some(4.0));
+    assert_eq!(s.boards.iter().map(|b| (b.board.as_str(), b.played, b.won)).collect::<Vec<_>>(), [("beginner", 6, 4), ("12x9/10 (custom)", 1, 1)]);
+    assert_eq!(s.boards[0].best_time_ms, Some(8_000));
+    assert_eq!(s.histogram.iter().map(|b| b.count).collect::<Vec<_>>(), [2, 2, 1, 0, 0, 0, 0]);
+    assert_eq!(log.players(), ["ann", "bob"]);
+    assert_eq!(log.summaries(Some("bob"))[0].win_rate, 0.0);
+    assert!(stats::render(&s).contains("Games 7  Won 5 (71%)  Streak 1 (best 2)"));
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn the_log_survives_restarts_and_skips_damaged_lines() {
+    let path = temp_file("lifetime-file", "stats.txt");
+    let mut log = StatsLog::load(&path);
+    log.record(game("ann", 9, true, 30)).unwrap();
+    let mut text = fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with("minesweeper-stats 1\n"));
+    text.push_str("ann garbage\n");
+    fs::write(&path, text).unwrap();
+    let mut log = StatsLog::load(&path);
+    log.record(game("ann", 9, false, 4)).unwrap();
+    let reloaded = StatsLog::load(&path);
+    assert_eq!(reloaded.games(), [game("ann", 9, true, 30), game("ann", 9, false, 4)]);
+    assert_eq!(fs::read_to_string(&path).unwrap().matches("minesweeper-stats").count(), 1);
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn only_finished_games_are_recorded() {
+    let mut b = Board::new(3, 3, 8, 1).unwrap();
+    assert!(GameRecord::from_board(&b, "ann").is_none());
+    b.reveal(1, 1);
+    let g = GameRecord::from_board(&b, "Ann Lee").expect("a single safe cell wins at once");
+    assert!(g.won);
+    assert_eq!((g.player.as_str(), g.bbbv, g.solved_bbbv, g.clicks), ("Ann_Lee", 1, 1, 1));
+}
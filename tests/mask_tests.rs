@@ -0,0 +1,64 @@
+mod common;
+
+use std::time::Duration;
+
+use minesweeper::engine::mask::{Mask, MaskError};
+use minesweeper::engine::replay::{self, Player, Replay, Step};
+use minesweeper::engine::{save, Action, Board, RevealResult};
+use common::position;
+
+const DONUT: &str = "\n  #####\n  ##  ###\n  #    ##\n  ##  ###\n  #####\n\n";
+
+#[test]
+fn parse_trims_blank_border_and_pads_rows() {
+    let m = Mask::parse(DONUT).expect("mask");
+    assert_eq!((m.width(), m.height()), (7, 5));
+    assert!(m.playable(0, 0) && m.playable(6, 1) && !m.playable(5, 0));
+    assert!(!m.playable(2, 2) && !m.playable(7, 0));
+    assert_eq!(m.cells(), 23);
+    assert!(matches!(Mask::parse("  \n\n"), Err(MaskError::Empty)));
+    assert!(matches!(Mask::parse("##\n#x#\n"), Err(MaskError::InvalidChar { x: 1, y: 1, ch: 'x' })));
+}
+
+#[test]
+fn holes_take_no_part_in_play() {
+    let mask = Mask::parse(DONUT).unwrap();
+    assert!(Board::from_mask(mask.clone(), 23, 1).is_err());
+    let mut b = Board::from_mask(mask, 6, 99).expect("board");
+    assert_eq!(b.remaining_safe(), 17);
+    assert_eq!(b.reveal(3, 2), RevealResult::NoOp);
+    assert!(!b.toggle_flag(3, 2));
+    assert_eq!(b.reveal(0, 0), RevealResult::RevealedSafe);
+    let mut mines = 0;
+    for y in 0..b.height() {
+        for x in 0..b.width() {
+            let c = b.cell(x, y).unwrap();
+            if !b.playable(x, y) {
+                assert!(!c.is_mine() && !c.revealed(), "hole at ({},{}) was used", x, y);
+                continue;
+            }
+            assert!(b.neighbors(x, y).all(|(nx, ny)| b.playable(nx, ny)));
+            if c.is_mine() { mines += 1; }
+        }
+    }
+    assert_eq!(mines, 6);
+}
+
+#[test]
+fn shaped_boards_survive_save_and_replay() {
+    let mut b = Board::from_mask(Mask::parse(DONUT).unwrap(), 6, 7).expect("board");
+    let mut rec = Replay::new(&b);
+    for (i, step) in [Step::Move(Action::Reveal(0, 0)), Step::Move(Action::ToggleFlag(6, 2))].into_iter().enumerate() {
+        replay::apply(&mut b, step);
+        rec.push(Duration::from_millis(100 * i as u64), step);
+    }
+    let loaded = save::decode(&save::encode(&b)).expect("decode");
+    assert_eq!(loaded.mask(), b.mask());
+    assert_eq!(loaded.remaining_safe(), b.remaining_safe());
+
+    let decoded = Replay::decode(&rec.encode()).expect("decode replay");
+    assert_eq!(decoded, rec);
+    let mut player = Player::new(decoded).expect("player");
+    while player.step().is_some() {}
+    assert_eq!(position(player.board()), position(&b));
+}
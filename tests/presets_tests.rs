@@ -0,0 +1,40 @@
+use std::fs;
+
+use minesweeper::presets::{self, Preset};
+
+#[test]
+fn parse_user_file_reports_bad_lines() {
+    let text = "# my presets\n\ntiny = 5x5/3\nWide=40X12 / 80\nbroken = 10x10\n= 3x3/1\n";
+    let (presets, errors) = presets::parse_user(text);
+    let specs: Vec<(String, String)> = presets.iter().map(|p| (p.name.clone(), p.spec())).collect();
+    assert_eq!(specs, vec![("tiny".into(), "5x5/3".into()), ("wide".into(), "40x12/80".into())]);
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].starts_with("line 5:"), "{}", errors[0]);
+}
+
+#[test]
+fn user_presets_extend_and_override_builtins() {
+    let dir = std::env::temp_dir().join(format!("minesweeper-test-presets-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("presets.txt");
+    fs::write(&path, "expert = 30x16/120\nhuge = 100x100/2000\n").unwrap();
+    let (all, errors) = presets::load(&path);
+    assert!(errors.is_empty());
+    let names: Vec<&str> = all.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["beginner", "intermediate", "expert", "huge"]);
+    assert_eq!(all[2].mines, 120);
+    // A missing file just yields the built-ins.
+    let (builtin, errors) = presets::load(&dir.join("missing.txt"));
+    assert_eq!(builtin, presets::builtin());
+    assert!(errors.is_empty());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn find_by_name_or_spec() {
+    let all = presets::builtin();
+    let expert = presets::find(&all, "Expert").unwrap();
+    assert_eq!((expert.width, expert.height, expert.mines), (30, 16, 99));
+    assert_eq!(presets::find(&all, "12x8/20"), Preset::parse_spec("custom", "12x8/20"));
+    assert!(presets::find(&all, "nightmare").is_none());
+}
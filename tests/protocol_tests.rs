@@ -0,0 +1,77 @@
+use minesweeper::engine::{Board, Topology};
+use minesweeper::protocol::{self, Session};
+use serde_json::Value;
+
+fn json(s: &str) -> Value { serde_json::from_str(s).expect("valid json") }
+
+#[test]
+fn reveal_reports_new_cells_and_status() {
+    let mut s = Session::new(Board::new(9, 9, 10, 12345).unwrap());
+    let hello = json(&s.hello().to_json());
+    assert_eq!(hello["op"], "hello");
+    assert_eq!(hello["version"], protocol::VERSION);
+    assert_eq!(hello["game"]["seed"], 12345);
+
+    let r = json(&s.handle(r#"{"op":"reveal","x":0,"y":0}"#).to_json());
+    assert_eq!(r["ok"], true);
+    assert_eq!(r["result"], "revealed");
+    let cells = r["revealed"].as_array().unwrap();
+    assert!(!cells.is_empty());
+    for c in cells {
+        let (x, y) = (c["x"].as_u64().unwrap() as usize, c["y"].as_u64().unwrap() as usize);
+        assert_eq!(c["n"].as_u64().unwrap() as u8, s.board().cell(x, y).unwrap().adjacent());
+    }
+    assert_eq!(r["status"]["state"], "playing");
+    assert_eq!(r["status"]["remaining_safe"], 71 - cells.len() as u64);
+
+    let state = json(&s.handle(r#"{"op":"state"}"#).to_json());
+    let rows = state["board"].as_array().unwrap();
+    assert_eq!(rows.len(), 9);
+    assert_eq!(rows.iter().map(|r| r.as_str().unwrap().chars().filter(char::is_ascii_digit).count()).sum::<usize>(), cells.len());
+}
+
+#[test]
+fn errors_have_codes_and_undo_covers_cells() {
+    let mut s = Session::new(Board::new(9, 9, 10, 12345).unwrap());
+    let r = json(&s.handle(r#"{"op":"reveal","x":9,"y":0}"#).to_json());
+    assert_eq!(r["ok"], false);
+    assert_eq!(r["error"], "out_of_bounds");
+    assert_eq!(json(&s.handle("not json").to_json())["error"], "bad_request");
+    assert_eq!(json(&s.handle(r#"{"op":"undo"}"#).to_json())["error"], "nothing_to_undo");
+
+    let opened = json(&s.handle(r#"{"op":"reveal","x":0,"y":0}"#).to_json())["revealed"].as_array().unwrap().len();
+    let undo = json(&s.handle(r#"{"op":"undo"}"#).to_json());
+    assert_eq!(undo["result"], "undone");
+    assert_eq!(undo["covered"].as_array().unwrap().len(), opened);
+    assert_eq!(json(&s.handle(r#"{"op":"flag","x":3,"y":3}"#).to_json())["result"], "flagged");
+    assert_eq!(json(&s.handle(r#"{"op":"flag","x":3,"y":3}"#).to_json())["status"]["mines_left"], 10);
+    let r = json(&s.handle(r#"{"op":"new","topology":"hex","wrap":true,"height":9}"#).to_json());
+    assert_eq!(r["error"], "invalid_dimensions");
+    assert_eq!(s.board().topology(), Topology::Square, "the old game stays");
+}
+
+#[test]
+fn run_speaks_one_line_per_request() {
+    let input = "{\"op\":\"new\",\"width\":5,\"height\":4,\"mines\":3,\"seed\":7}\n\n{\"op\":\"reveal\",\"x\":2,\"y\":2}\n{\"op\":\"quit\"}\n{\"op\":\"state\"}\n";
+    let mut out = Vec::new();
+    let mut s = Session::new(Board::new(9, 9, 10, 1).unwrap());
+    protocol::run(&mut s, input.as_bytes(), &mut out).unwrap();
+    let lines: Vec<Value> = String::from_utf8(out).unwrap().lines().map(json).collect();
+    assert_eq!(lines.len(), 4, "hello, new, reveal, quit");
+    assert_eq!(lines[1]["game"]["width"], 5);
+    assert_eq!(lines[1]["game"]["seed"], 7);
+    assert_eq!(lines[3]["result"], "bye");
+    assert!(s.finished());
+    assert_eq!(s.board().mines(), 3);
+}
+
+#[test]
+fn hitting_a_mine_reports_it() {
+    let mut s = Session::new(Board::new(9, 9, 10, 42).unwrap());
+    s.handle(r#"{"op":"reveal","x":4,"y":4}"#);
+    let (x, y) = (0..81).map(|i| (i % 9, i / 9)).find(|&(x, y)| s.board().cell(x, y).unwrap().is_mine()).unwrap();
+    let r = json(&s.handle(&format!(r#"{{"op":"reveal","x":{},"y":{}}}"#, x, y)).to_json());
+    assert_eq!(r["result"], "hit_mine");
+    assert_eq!(r["status"]["state"], "lost");
+    assert_eq!(r["revealed"], serde_json::json!([{"x": x, "y": y, "mine": true}]));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use minesweeper::engine::race::Race;
+use minesweeper::engine::{Action, Board, Error};
+use common::cells;
+
+#[test]
+fn both_players_get_the_same_opened_board() {
+    let race = Race::new(Board::new(16, 16, 40, 777).unwrap());
+    assert_eq!(race.opening(), (8, 8));
+    let (a, b) = (race.board(0), race.board(1));
+    assert!(a.cell(8, 8).unwrap().revealed());
+    assert_eq!(a.seed(), b.seed());
+    for (x, y) in cells(a) { assert_eq!(a.cell(x, y), b.cell(x, y)); }
+    assert!(race.outcome().is_none());
+}
+
+#[test]
+fn hitting_a_mine_hands_the_win_to_the_opponent() {
+    let mut race = Race::new(Board::new(9, 9, 10, 42).unwrap());
+    let mine = cells(race.board(1)).into_iter().find(|&(x, y)| race.board(1).cell(x, y).unwrap().is_mine()).unwrap();
+    race.play(1, Action::Reveal(mine.0, mine.1)).unwrap();
+    let outcome = race.outcome().expect("decided");
+    assert_eq!(outcome.winner, Some(0));
+    assert!(outcome.by_mine);
+    assert!(matches!(race.play(0, Action::Reveal(0, 0)), Err(Error::GameOver)));
+}
+
+#[test]
+fn clearing_the_board_first_wins() {
+    let mut race = Race::new(Board::new(9, 9, 10, 42).unwrap());
+    let safe: Vec<_> = cells(race.board(0)).into_iter().filter(|&(x, y)| !race.board(0).cell(x, y).unwrap().is_mine()).collect();
+    for (x, y) in safe {
+        if race.outcome().is_some() { break; }
+        let _ = race.play(0, Action::Reveal(x, y));
+    }
+    let outcome = race.outcome().expect("decided");
+    assert_eq!(outcome.winner, Some(0));
+    assert!(!outcome.by_mine);
+    assert!(race.board(0).won() && !race.board(1).won());
+}
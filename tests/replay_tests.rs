@@ -0,0 +1,41 @@
+mod common;
+
+use std::time::Duration;
+
+use minesweeper::engine::replay::{Player, Replay, Step};
+use minesweeper::engine::{Action, Board};
+use common::position;
+
+#[test]
+fn replay_reproduces_the_recorded_game() {
+    let mut board = Board::new(9, 9, 10, 12345).expect("board");
+    let mut rec = Replay::new(&board);
+    let steps = [
+        Step::Move(Action::Reveal(4, 4)),
+        Step::Move(Action::ToggleFlag(0, 8)),
+        Step::Move(Action::Reveal(8, 0)),
+        Step::Undo,
+        Step::Redo,
+    ];
+    for (i, &step) in steps.iter().enumerate() {
+        minesweeper::engine::replay::apply(&mut board, step);
+        rec.push(Duration::from_millis(250 * i as u64), step);
+    }
+
+    let decoded = Replay::decode(&rec.encode()).expect("decode");
+    assert_eq!(decoded, rec);
+
+    let mut player = Player::new(decoded).expect("player");
+    assert_eq!(player.next_delay(), Some(Duration::ZERO));
+    player.step();
+    assert_eq!(player.next_delay(), Some(Duration::from_millis(250)));
+    while player.step().is_some() {}
+    assert!(player.finished());
+    assert_eq!(position(player.board()), position(&board));
+}
+
+#[test]
+fn replay_rejects_unknown_events() {
+    let text = "minesweeper-replay 1\nsize 9 9\nmines 10\nseed 1\nevents\n0 explode 1 1\n";
+    assert!(Replay::decode(text).is_err());
+}
@@ -0,0 +1,65 @@
+use minesweeper::engine::replay::Replay;
+use minesweeper::engine::rng::{self, Algorithm, MineRng, SeedError, SeedSpec, SplitMix64, XorShift64};
+use minesweeper::engine::{save, Board, Error, Topology};
+
+fn mines(b: &Board) -> Vec<(usize, usize)> {
+    (0..b.height()).flat_map(|y| (0..b.width()).map(move |x| (x, y))).filter(|&(x, y)| b.cell(x, y).unwrap().is_mine()).collect()
+}
+
+#[test]
+fn seed_strings_round_trip_and_reject_garbage() {
+    let spec: SeedSpec = "xs64:12345:30x16:99".parse().unwrap();
+    assert_eq!((spec.algorithm, spec.seed, spec.width, spec.height, spec.mines), (Algorithm::XorShift64, 12345, 30, 16, 99));
+    assert_eq!(spec.first_click, None);
+    let full = "sm64:7:16x16:40:ng1:hex:wrap:8,9";
+    let spec: SeedSpec = full.parse().unwrap();
+    assert!(spec.no_guess && spec.wrap && spec.topology == Topology::Hex);
+    assert_eq!(spec.first_click, Some((7, 8)));
+    assert_eq!(spec.to_string(), full);
+    assert_eq!("sm64:7:16x16:40:ng:hex:wrap:8,9".parse::<SeedSpec>().unwrap(), spec);
+    assert!(matches!("sm64:7:16x16:40:ng2".parse::<SeedSpec>(), Err(SeedError::UnsupportedNoGuess(2))));
+    for bad in ["xs64:1:9x9", "mt:1:9x9:10", "xs64:0:9x9:10", "xs64:1:9by9:10", "xs64:1:9x9:10:0,3", "xs64:1:9x9:10:fast", "xs64:1:9x9:10:ngx"] {
+        assert!(bad.parse::<SeedSpec>().is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn a_seed_string_reproduces_the_board() {
+    for alg in [Algorithm::XorShift64, Algorithm::SplitMix64] {
+        let mut b = Board::new(16, 16, 40, 99).unwrap().with_rng(alg);
+        b.reveal(3, 5);
+        let spec = SeedSpec::of(&b).unwrap();
+        assert_eq!(spec.to_string(), format!("{}:99:16x16:40:4,6", alg));
+        let again = spec.to_string().parse::<SeedSpec>().unwrap().board().unwrap();
+        assert_eq!(mines(&again), mines(&b));
+        let loaded = save::decode(&save::encode(&b)).unwrap();
+        assert_eq!(SeedSpec::of(&loaded), Some(spec));
+        let mut replayed = Replay::decode(&Replay::new(&Board::new(16, 16, 40, 99).unwrap().with_rng(alg)).encode()).unwrap().board().unwrap();
+        replayed.reveal(3, 5);
+        assert_eq!(mines(&replayed), mines(&b));
+    }
+    let mut other = Board::new(16, 16, 40, 99).unwrap().with_rng(Algorithm::SplitMix64);
+    other.reveal(3, 5);
+    let mut xs = Board::new(16, 16, 40, 99).unwrap();
+    xs.reveal(3, 5);
+    assert_ne!(mines(&other), mines(&xs));
+    assert!(matches!("xs64:1:9x9:10:10,1".parse::<SeedSpec>().unwrap().board(), Err(Error::OutOfBounds { .. })));
+    assert!(matches!("xs64:1:9x9:10:hex:wrap".parse::<SeedSpec>().unwrap().board(), Err(Error::HexWrapOddHeight { height: 9 })));
+}
+
+#[test]
+fn generators_and_shuffle_follow_the_documented_algorithm() {
+    let mut xs = XorShift64::new(1);
+    assert_eq!(xs.next_u64(), 1_082_269_761);
+    assert_eq!(SplitMix64::new(0).next_u64(), 0xE220_A839_7B1D_CDAF);
+    // Fisher–Yates from the back: each swap index is `(next_u64() >> 1) % (i + 1)`.
+    let mut expected: Vec<usize> = (0..10).collect();
+    let mut g = XorShift64::new(42);
+    for i in (1..10).rev() {
+        let j = ((g.next_u64() >> 1) % (i as u64 + 1)) as usize;
+        expected.swap(i, j);
+    }
+    let mut items: Vec<usize> = (0..10).collect();
+    rng::shuffle(&mut items, &mut XorShift64::new(42));
+    assert_eq!(items, expected);
+}
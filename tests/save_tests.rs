@@ -0,0 +1,86 @@
+use minesweeper::engine::save::{self, SaveError};
+use minesweeper::engine::{Board, Error, Topology};
+
+fn played_board() -> Board {
+    let mut b = Board::new(9, 9, 10, 12345).expect("board");
+    let _ = b.reveal(0, 0);
+    let _ = b.toggle_flag(8, 8);
+    b
+}
+
+#[test]
+fn save_round_trip_preserves_state() {
+    let b = played_board();
+    let text = save::encode(&b);
+    let loaded = save::decode(&text).expect("decode");
+    // The timer keeps running, so only compare everything but the elapsed time.
+    let untimed = |t: &str| t.lines().filter(|l| !l.starts_with("elapsed_ms")).collect::<Vec<_>>().join("\n");
+    assert_eq!(untimed(&save::encode(&loaded)), untimed(&text));
+    assert_eq!(loaded.remaining_safe(), b.remaining_safe());
+    assert_eq!(loaded.seed(), 12345);
+    for y in 0..9 {
+        for x in 0..9 {
+            let (a, c) = (b.cell(x, y).unwrap(), loaded.cell(x, y).unwrap());
+            assert_eq!((a.is_mine(), a.revealed(), a.flagged(), a.adjacent()), (c.is_mine(), c.revealed(), c.flagged(), c.adjacent()));
+        }
+    }
+}
+
+#[test]
+fn load_rejects_adjacency_mismatch() {
+    let text = save::encode(&played_board());
+    // Turn the first revealed number into a wrong one.
+    let grid_start = text.find("grid\n").unwrap() + 5;
+    let pos = text[grid_start..].find(|c: char| ('1'..='7').contains(&c)).unwrap() + grid_start;
+    let wrong = ((text.as_bytes()[pos] - b'0') % 7 + 1 + b'0') as char;
+    let mut bad = text.clone();
+    bad.replace_range(pos..pos + 1, &wrong.to_string());
+    assert!(matches!(save::decode(&bad), Err(SaveError::AdjacencyMismatch { .. })));
+}
+
+#[test]
+fn load_rejects_mine_count_mismatch() {
+    let text = save::encode(&played_board()).replace("mines 10", "mines 11");
+    match save::decode(&text) {
+        Err(SaveError::MineCountMismatch { expected: 11, found: 10 }) => {}
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn load_rejects_sizes_that_do_not_fit() {
+    let text = "minesweeper-save 1\nsize 18446744073709551615 1\nmines 1\nseed 1\ninitialized false\nalive true\nwon false\ngrid\n..\n";
+    assert!(matches!(save::decode(text), Err(SaveError::RowLength { row: 0, found: 2, .. })));
+    assert!(matches!(Board::new(usize::MAX, 2, 1, 1), Err(Error::InvalidDimensions { .. })));
+}
+
+#[test]
+fn load_rejects_foreign_files() {
+    assert!(matches!(save::decode("hello\n"), Err(SaveError::BadHeader)));
+    assert!(matches!(save::decode("minesweeper-save 99\n"), Err(SaveError::UnsupportedVersion(99))));
+}
+
+#[test]
+fn hex_boards_keep_their_topology() {
+    let mut b = Board::new(7, 6, 8, 77).expect("board").with_topology(Topology::Hex).unwrap();
+    let _ = b.reveal(3, 3);
+    let loaded = save::decode(&save::encode(&b)).expect("decode");
+    assert_eq!(loaded.topology(), Topology::Hex);
+    // Hex adjacency differs from square adjacency, so this only validates under hex rules.
+    let square = save::encode(&b).replace("topology hex\n", "");
+    assert!(matches!(save::decode(&square), Err(SaveError::AdjacencyMismatch { .. })));
+}
+
+#[test]
+fn question_marks_survive_a_save() {
+    let mut b = Board::new(9, 9, 10, 12345).expect("board").with_question_marks(true);
+    let _ = b.reveal(0, 0);
+    let _ = b.toggle_flag(8, 8);
+    let _ = b.toggle_flag(8, 8);
+    let text = save::encode(&b);
+    assert!(text.contains("question_marks true"));
+    let loaded = save::decode(&text).expect("decode");
+    assert!(loaded.question_marks());
+    assert!(loaded.cell(8, 8).unwrap().questioned());
+    assert_eq!(loaded.cell(8, 8).unwrap().is_mine(), b.cell(8, 8).unwrap().is_mine());
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use minesweeper::engine::solver::solve;
+use minesweeper::engine::rng::Algorithm;
+use minesweeper::engine::{save, Board, Clock, Topology};
+use minesweeper::scores::{Category, Score, ScoreTable, TOP_N};
+use common::temp_file;
+
+struct ManualClock(Mutex<Duration>);
+impl Clock for ManualClock {
+    fn now(&self) -> Duration { *self.0.lock().unwrap() }
+}
+
+fn won_board(seed: u64, secs: u64) -> Board {
+    let clock = Arc::new(ManualClock(Mutex::new(Duration::ZERO)));
+    let mut b = Board::new(9, 9, 10, seed).expect("board").with_no_guess(true).with_clock(clock.clone());
+    let _ = b.reveal(4, 4);
+    *clock.0.lock().unwrap() = Duration::from_secs(secs);
+    while !b.won() {
+        let d = solve(&b);
+        assert!(!d.safe.is_empty(), "no-guess board got stuck");
+        for (x, y) in d.safe { let _ = b.reveal(x, y); }
+    }
+    b
+}
+
+#[test]
+fn only_timed_wins_produce_scores() {
+    assert!(Score::from_board(&Board::new(9, 9, 10, 1).unwrap()).is_none());
+    let s = Score::from_board(&won_board(3, 42)).expect("score");
+    assert_eq!(s.time, Duration::from_secs(42));
+    let spec = s.board.expect("seed string");
+    assert_eq!((spec.seed, spec.first_click), (3, Some((4, 4))));
+    assert!(!s.category.custom());
+}
+
+#[test]
+fn loaded_or_undone_wins_do_not_score() {
+    let loaded = save::decode(&save::encode(&won_board(3, 42))).unwrap();
+    assert!(loaded.won() && loaded.timer_restored());
+    assert!(Score::from_board(&loaded).is_none());
+    let mut b = won_board(4, 42);
+    b.undo();
+    b.redo();
+    assert!(b.won() && b.undo_used());
+    assert!(Score::from_board(&b).is_none());
+}
+
+#[test]
+fn scores_round_trip_and_rank() {
+    let path = temp_file("rank", "scores.txt");
+    let mut table = ScoreTable::load(&path);
+    assert!(table.categories().is_empty(), "missing file is an empty table");
+    assert_eq!(table.submit(Score::from_board(&won_board(1, 50)).unwrap()), Some(1));
+    assert_eq!(table.submit(Score::from_board(&won_board(2, 30)).unwrap()), Some(1));
+    assert_eq!(table.submit(Score::from_board(&won_board(3, 40)).unwrap()), Some(2));
+    table.save().expect("save");
+
+    let loaded = ScoreTable::load(&path);
+    let cat = Category { width: 9, height: 9, mines: 10, no_guess: true, topology: Topology::Square, wrap: false, mask: None };
+    let times: Vec<u64> = loaded.best(cat).iter().map(|s| s.time.as_secs()).collect();
+    assert_eq!(times, vec![30, 40, 50]);
+    assert_eq!(loaded.best(cat)[0].board, Score::from_board(&won_board(2, 30)).unwrap().board);
+}
+
+#[test]
+fn corrupt_lines_are_skipped_and_table_is_capped() {
+    let path = temp_file("corrupt", "scores.txt");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut text = String::from("minesweeper-scores 1\ngarbage line\n9 9 10 0 0 notanumber 1 0\n");
+    for i in 0..(TOP_N + 5) { text.push_str(&format!("9 9 10 0 0 {} {} 0\n", 1000 * (i + 1), i)); }
+    fs::write(&path, text).unwrap();
+
+    let table = ScoreTable::load(&path);
+    let best = table.best(Category { width: 9, height: 9, mines: 10, no_guess: false, topology: Topology::Square, wrap: false, mask: None });
+    assert_eq!(best.len(), TOP_N);
+    assert_eq!(best[0].time, Duration::from_secs(1));
+}
+
+#[test]
+fn old_bare_seeds_read_as_xs64_seed_strings() {
+    let path = temp_file("old-seeds", "scores.txt");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "minesweeper-scores 1\n9 9 10 1 0 5000 77 0\n").unwrap();
+    let table = ScoreTable::load(&path);
+    let cat = Category { width: 9, height: 9, mines: 10, no_guess: true, topology: Topology::Square, wrap: false, mask: None };
+    let spec = table.best(cat)[0].board.expect("seed string");
+    assert_eq!((spec.algorithm, spec.seed, spec.first_click, spec.no_guess), (Algorithm::XorShift64, 77, None, true));
+    assert!(table.render().contains("xs64:77:9x9:10:ng1"));
+}
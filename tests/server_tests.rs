@@ -0,0 +1,121 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use minesweeper::engine::Board;
+use minesweeper::server::Server;
+use serde_json::Value;
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    fn connect(addr: &str) -> Self {
+        let writer = TcpStream::connect(addr).expect("connect");
+        let mut c = Self { reader: BufReader::new(writer.try_clone().unwrap()), writer };
+        assert_eq!(c.read()["op"], "hello");
+        c
+    }
+
+    fn read(&mut self) -> Value {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).expect("response");
+        serde_json::from_str(&line).expect("json")
+    }
+
+    fn send(&mut self, req: &str) -> Value {
+        writeln!(self.writer, "{}", req).unwrap();
+        self.read()
+    }
+}
+
+fn start() -> (String, Arc<Server>) { start_with(Server::new(Board::new(9, 9, 10, 1).unwrap())) }
+
+fn start_with(server: Server) -> (String, Arc<Server>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().unwrap().to_string();
+    let server = Arc::new(server);
+    let s = Arc::clone(&server);
+    thread::spawn(move || s.serve(listener));
+    (addr, server)
+}
+
+#[test]
+fn sessions_are_independent_and_shared_by_id() {
+    let (addr, server) = start();
+    let mut a = Client::connect(&addr);
+    let mut b = Client::connect(&addr);
+    let sa = a.send(r#"{"op":"new","seed":5}"#);
+    let sb = b.send(r#"{"op":"new","width":5,"height":5,"mines":3}"#);
+    let (ida, idb) = (sa["session"].as_u64().unwrap(), sb["session"].as_u64().unwrap());
+    assert_ne!(ida, idb);
+    assert_eq!(sa["game"]["width"], 9);
+    assert_eq!(sb["game"]["width"], 5);
+    assert_eq!(server.sessions(), 2);
+
+    let r = a.send(&format!(r#"{{"op":"reveal","x":0,"y":0,"session":{}}}"#, ida));
+    assert_eq!(r["ok"], true);
+    assert_eq!(r["session"], ida);
+    // Another connection sees the same game through its id.
+    let state = b.send(&format!(r#"{{"op":"state","session":{}}}"#, ida));
+    assert_eq!(state["status"]["remaining_safe"], r["status"]["remaining_safe"]);
+    let other = b.send(&format!(r#"{{"op":"state","session":{}}}"#, idb));
+    assert_eq!(other["status"]["remaining_safe"], 22);
+}
+
+#[test]
+fn session_errors_and_close() {
+    let (addr, server) = start();
+    let mut c = Client::connect(&addr);
+    assert_eq!(c.send(r#"{"op":"reveal","x":0,"y":0}"#)["error"], "no_session");
+    assert_eq!(c.send(r#"{"op":"state","session":999}"#)["error"], "unknown_session");
+    assert_eq!(c.send(r#"{"op":"new","mines":81}"#)["error"], "too_many_mines");
+    let id = c.send(r#"{"op":"new"}"#)["session"].as_u64().unwrap();
+    assert_eq!(c.send(&format!(r#"{{"op":"close","session":{}}}"#, id))["result"], "closed");
+    assert_eq!(server.sessions(), 0);
+    assert_eq!(c.send(r#"{"op":"quit"}"#)["result"], "bye");
+    let mut rest = String::new();
+    assert_eq!(c.reader.read_line(&mut rest).unwrap(), 0, "server hung up");
+}
+
+#[test]
+fn connections_are_capped_and_idle_ones_time_out() {
+    let server = Server::new(Board::new(9, 9, 10, 1).unwrap()).with_max_connections(1).with_idle_timeout(Duration::from_millis(300));
+    let (addr, server) = start_with(server);
+    let mut first = Client::connect(&addr);
+    let id = first.send(r#"{"op":"new"}"#)["session"].as_u64().unwrap();
+    let mut turned_away = BufReader::new(TcpStream::connect(&addr).unwrap());
+    let mut line = String::new();
+    turned_away.read_line(&mut line).unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&line).unwrap()["error"], "server_full");
+
+    // Silence closes the connection, frees its slot and lets the session expire.
+    assert_eq!(first.read()["error"], "idle_timeout");
+    thread::sleep(Duration::from_millis(100));
+    let mut second = Client::connect(&addr);
+    assert_eq!(second.send(&format!(r#"{{"op":"state","session":{}}}"#, id))["error"], "unknown_session");
+    assert_eq!(server.sessions(), 0);
+}
+
+#[test]
+fn oversized_boards_and_extra_sessions_are_refused() {
+    let (addr, server) = start_with(Server::new(Board::new(9, 9, 10, 1).unwrap()).with_max_sessions(2));
+    let mut c = Client::connect(&addr);
+    let r = c.send(r#"{"op":"new","width":1000000,"height":1000000,"mines":1}"#);
+    assert_eq!((r["ok"].as_bool(), r["error"].as_str()), (Some(false), Some("too_large")));
+    assert_eq!(c.send(r#"{"op":"new","width":1000,"height":1000,"mines":1}"#)["error"], "too_large");
+    assert_eq!(server.sessions(), 0);
+
+    let first = c.send(r#"{"op":"new"}"#)["session"].as_u64().unwrap();
+    c.send(r#"{"op":"new"}"#);
+    assert_eq!(c.send(r#"{"op":"new"}"#)["error"], "too_many_sessions");
+    // Closing one frees its place; other connections have their own allowance.
+    c.send(&format!(r#"{{"op":"close","session":{}}}"#, first));
+    assert_eq!(c.send(r#"{"op":"new"}"#)["ok"], true);
+    assert_eq!(Client::connect(&addr).send(r#"{"op":"new"}"#)["ok"], true);
+    assert_eq!(server.sessions(), 3);
+}
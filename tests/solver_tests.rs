@@ -0,0 +1,156 @@
+use minesweeper::engine::save;
+use minesweeper::engine::solver::{hint, probabilities, solve, HintKind};
+use minesweeper::engine::{Board, RevealResult};
+
+fn board_from_grid(width: usize, height: usize, mines: usize, grid: &str) -> Board {
+    let text = format!(
+        "minesweeper-save 1\nsize {} {}\nmines {}\nseed 1\ninitialized true\nalive true\nwon false\ngrid\n{}\n",
+        width, height, mines, grid
+    );
+    save::decode(&text).expect("valid test board")
+}
+
+#[test]
+fn one_two_one_pattern() {
+    let b = board_from_grid(3, 2, 2, "*.*\n121");
+    let d = solve(&b);
+    assert_eq!(d.mines.into_iter().collect::<Vec<_>>(), vec![(0, 0), (2, 0)]);
+    assert_eq!(d.safe.into_iter().collect::<Vec<_>>(), vec![(1, 0)]);
+}
+
+#[test]
+fn global_mine_count_clears_interior() {
+    // The flag accounts for the only mine, so (3,0) is safe even though no
+    // number touches it.
+    let b = board_from_grid(4, 1, 1, "F1..");
+    let d = solve(&b);
+    assert_eq!(d.safe.into_iter().collect::<Vec<_>>(), vec![(2, 0), (3, 0)]);
+    assert!(d.mines.is_empty());
+}
+
+#[test]
+fn deductions_are_always_correct_and_make_progress() {
+    for seed in 1..40u64 {
+        let mut b = Board::new(16, 16, 40, seed).expect("board");
+        let _ = b.reveal(8, 8);
+        while b.alive() && !b.won() {
+            let d = solve(&b);
+            if d.is_empty() { break; }
+            for &(x, y) in &d.mines {
+                assert!(b.cell(x, y).unwrap().is_mine(), "seed {}: ({},{}) is not a mine", seed, x, y);
+                b.toggle_flag(x, y);
+            }
+            for &(x, y) in &d.safe {
+                assert!(!b.cell(x, y).unwrap().is_mine(), "seed {}: ({},{}) is a mine", seed, x, y);
+                let _ = b.reveal(x, y);
+            }
+        }
+        assert!(b.alive());
+    }
+}
+
+fn play_by_logic(b: &mut Board) {
+    loop {
+        let d = solve(b);
+        if d.safe.is_empty() { break; }
+        for (x, y) in d.safe { let _ = b.reveal(x, y); }
+    }
+}
+
+#[test]
+fn no_guess_boards_are_solvable_by_logic() {
+    for seed in 1..6u64 {
+        let mut b = Board::new(16, 16, 40, seed).expect("board").with_no_guess(true);
+        let _ = b.reveal(3, 5);
+        play_by_logic(&mut b);
+        assert!(b.won(), "seed {} needed a guess", seed);
+    }
+}
+
+#[test]
+fn no_guess_layout_is_reproducible() {
+    let layout = |seed| {
+        let mut b = Board::new(16, 16, 40, seed).expect("board").with_no_guess(true);
+        let _ = b.reveal(7, 7);
+        (0..256).filter(|&i| b.cell(i % 16, i / 16).unwrap().is_mine()).collect::<Vec<_>>()
+    };
+    assert_eq!(layout(77), layout(77));
+    assert_ne!(layout(77), layout(78));
+}
+
+#[test]
+fn large_no_guess_boards_generate_promptly() {
+    let start = std::time::Instant::now();
+    let mut b = Board::new(64, 64, 820, 7).expect("board").with_no_guess(true);
+    assert_eq!(b.reveal(32, 32), RevealResult::RevealedSafe);
+    assert!(start.elapsed().as_secs() < 20, "took {:?}", start.elapsed());
+}
+
+#[test]
+fn probabilities_of_certain_cells() {
+    let b = board_from_grid(3, 2, 2, "*.*\n121");
+    let p = probabilities(&b);
+    assert_eq!(p.get(0, 0), Some(1.0));
+    assert_eq!(p.get(1, 0), Some(0.0));
+    assert_eq!(p.get(0, 1), None);
+    assert!(p.exact());
+}
+
+#[test]
+fn probabilities_match_brute_force() {
+    let mut checked = 0;
+    for seed in 1..12u64 {
+        let (w, h, m) = (5, 4, 5);
+        let mut b = Board::new(w, h, m, seed).expect("board");
+        let _ = b.reveal(0, 0);
+        if b.won() { continue; }
+        let covered: Vec<(usize, usize)> = (0..w * h).map(|i| (i % w, i / w)).filter(|&(x, y)| !b.cell(x, y).unwrap().revealed()).collect();
+
+        // Count every layout of `m` mines over the covered cells that agrees with the numbers.
+        let mut counts = vec![0u64; covered.len()];
+        let mut total = 0u64;
+        for mask in 0u64..(1 << covered.len()) {
+            if mask.count_ones() as usize != m { continue; }
+            let is_mine = |x: usize, y: usize| covered.iter().position(|&c| c == (x, y)).is_some_and(|i| mask >> i & 1 == 1);
+            let consistent = (0..w * h).map(|i| (i % w, i / w)).all(|(x, y)| {
+                let c = b.cell(x, y).unwrap();
+                !c.revealed() || b.neighbors(x, y).filter(|&(nx, ny)| is_mine(nx, ny)).count() == c.adjacent() as usize
+            });
+            if !consistent { continue; }
+            total += 1;
+            for (i, n) in counts.iter_mut().enumerate() { if mask >> i & 1 == 1 { *n += 1; } }
+        }
+
+        let p = probabilities(&b);
+        assert!(p.exact());
+        for (i, &(x, y)) in covered.iter().enumerate() {
+            let expected = counts[i] as f64 / total as f64;
+            let got = p.get(x, y).expect("covered cell has a probability");
+            assert!((got - expected).abs() < 1e-9, "seed {} ({},{}): {} vs {}", seed, x, y, got, expected);
+        }
+        checked += 1;
+    }
+    assert!(checked > 3);
+}
+
+#[test]
+fn hints_explain_the_simplest_rule() {
+    let h = hint(&board_from_grid(4, 1, 1, "F1..")).expect("hint");
+    assert_eq!((h.x, h.y, h.kind), (2, 0, HintKind::Safe));
+    assert_eq!(h.reason, "The 1 at (2,1) already touches a flag, so (3,1) is safe");
+
+    let h = hint(&board_from_grid(3, 2, 2, "*.*\n121")).expect("hint");
+    assert_eq!((h.x, h.y, h.kind), (2, 0, HintKind::Mine));
+    assert!(h.reason.contains("the 2 at (2,2)"), "{}", h.reason);
+}
+
+#[test]
+fn hint_falls_back_to_safest_guess() {
+    let b = Board::new(9, 9, 10, 5).expect("board");
+    let h = hint(&b).expect("hint");
+    match h.kind {
+        HintKind::Guess(p) => assert!((p - 10.0 / 81.0).abs() < 1e-9),
+        other => panic!("expected a guess, got {:?}", other),
+    }
+    assert!(h.reason.starts_with("No logical move"));
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use minesweeper::engine::save::{self, SaveError};
+use minesweeper::engine::stats::{Clicks, Stats};
+use minesweeper::engine::Board;
+
+// A single row `*.*....`: one opening (x = 3..=6) plus the isolated 2 at x = 1.
+fn strip() -> Board {
+    let text = "minesweeper-save 1\nsize 7 1\nmines 2\nseed 1\nno_guess false\ninitialized true\nalive true\nwon false\nelapsed_ms 0\ngrid\n*.*....\n";
+    save::decode(text).expect("decode")
+}
+
+#[test]
+fn bbbv_counts_openings_once_and_isolated_numbers_each() {
+    let mut b = strip();
+    assert_eq!((b.stats().bbbv, b.stats().solved_bbbv), (2, 0));
+    b.reveal(3, 0);
+    assert_eq!(b.stats().solved_bbbv, 0, "a border number alone does not solve its opening");
+    b.reveal(5, 0);
+    assert_eq!(b.stats().solved_bbbv, 1);
+    b.reveal(1, 0);
+    assert!(b.won());
+    assert_eq!(b.stats().solved_bbbv, 2);
+    assert_eq!(Board::new(9, 9, 10, 1).unwrap().stats().bbbv, 0, "no layout before the first reveal");
+}
+
+#[test]
+fn clicks_are_counted_by_kind_and_survive_undo_and_saves() {
+    let mut b = strip();
+    b.toggle_flag(0, 0);
+    b.reveal(1, 0);
+    b.reveal(1, 0);
+    b.chord(1, 0);
+    b.undo();
+    let clicks = b.stats().clicks;
+    assert_eq!(clicks, Clicks { left: 2, right: 1, chord: 1, wasted: 2 });
+    assert_eq!((clicks.total(), clicks.effective()), (4, 2));
+    assert_eq!(save::decode(&save::encode(&b)).unwrap().stats().clicks, clicks);
+    let forged = save::encode(&b).replace("clicks 2 1 1 2", "clicks 0 0 0 3");
+    assert!(matches!(save::decode(&forged), Err(SaveError::Inconsistent(_))));
+    assert_eq!(Clicks { wasted: 3, ..Clicks::default() }.effective(), 0);
+}
+
+#[test]
+fn rates_divide_solved_bbbv_by_time_and_clicks() {
+    let stats = Stats {
+        bbbv: 30,
+        solved_bbbv: 24,
+        clicks: Clicks { left: 20, right: 6, chord: 4, wasted: 6 },
+        elapsed: Duration::from_secs(12),
+    };
+    assert_eq!(stats.bbbv_per_sec(), Some(2.0));
+    assert_eq!(stats.efficiency(), Some(100.0));
+    assert_eq!(stats.ioe(), Some(0.8));
+    assert_eq!(Stats { elapsed: Duration::ZERO, clicks: Clicks::default(), ..stats }.ioe(), None);
+    assert!(stats.to_string().starts_with("3BV 24/30  3BV/s 2.00"));
+}